@@ -90,6 +90,10 @@ pub struct Args {
     #[arg(long, help_heading = "Source Options")]
     pub network_name: Option<String>,
 
+    /// Websocket RPC url for streamed datasets [default: ETH_WS_URL env var]
+    #[arg(long, value_name = "WS_RPC", help_heading = "Source Options")]
+    pub ws_rpc: Option<String>,
+
     /// Ratelimit on requests per second
     #[arg(short('l'), long, value_name = "limit", help_heading = "Acquisition Options")]
     pub requests_per_second: Option<u32>,
@@ -110,6 +114,10 @@ pub struct Args {
     #[arg(long, value_name = "M", help_heading = "Acquisition Options")]
     pub max_concurrent_chunks: Option<u64>,
 
+    /// Seconds to stream before exiting [default: until interrupted]
+    #[arg(long, value_name = "SECONDS", help_heading = "Acquisition Options")]
+    pub stream_duration: Option<u64>,
+
     /// Dry run, collect no data
     #[arg(short, long, help_heading = "Acquisition Options")]
     pub dry: bool,
@@ -151,6 +159,10 @@ pub struct Args {
     #[arg(long, help_heading = "Output Options", hide = true)]
     pub file_suffix: Option<String>,
 
+    /// Seconds of data per file for streamed datasets
+    #[arg(long, default_value_t = 3600, value_name = "SECONDS", help_heading = "Output Options")]
+    pub rotate_interval: u64,
+
     /// Overwrite existing files instead of skipping
     #[arg(long, help_heading = "Output Options")]
     pub overwrite: bool,
@@ -240,6 +252,106 @@ pub struct Args {
     #[arg(long, value_name = "SIG", help_heading = "Dataset-specific Options", num_args(1..))]
     pub event_signature: Option<String>,
 
+    /// Function or error signature(s) for decoding calls and reverts
+    #[arg(long, value_name = "SIG", help_heading = "Dataset-specific Options", num_args(1..))]
+    pub function_signature: Option<Vec<String>>,
+
+    /// JSON ABI file for decoding calls and reverts
+    #[arg(long, value_name = "PATH", help_heading = "Dataset-specific Options")]
+    pub abi: Option<String>,
+
+    /// CSV or SQLite dump(s) of text signatures for labeling selectors and topics,
+    /// as [function=|event=]PATH, kind defaults to the sqlite table or csv file name
+    #[arg(long, value_name = "SIGNATURES", help_heading = "Dataset-specific Options", num_args(1..))]
+    pub signatures: Option<Vec<String>>,
+
+    /// Solc storage layout(s) for decoding slots, as [ADDRESS=]PATH
+    #[arg(long, value_name = "LAYOUT", help_heading = "Dataset-specific Options", num_args(1..))]
+    pub storage_layout: Option<Vec<String>>,
+
+    /// Known mapping keys for recovering mapping entries from slots
+    #[arg(long, value_name = "KEY", help_heading = "Dataset-specific Options", num_args(1..))]
+    pub mapping_keys: Option<Vec<String>>,
+
+    /// JSON file listing pools and oracles to use for prices
+    #[arg(long, value_name = "PATH", help_heading = "Dataset-specific Options")]
+    pub price_sources: Option<String>,
+
+    /// Token id(s) or inclusive ranges start:end for erc721_owners
+    #[arg(long, value_name = "IDS", help_heading = "Dataset-specific Options", num_args(1..))]
+    pub token_ids: Option<Vec<String>>,
+
+    /// Transfer parquet file(s) or directories to replay instead of
+    /// calling the rpc, for erc721_owners and erc20_holders
+    #[arg(
+        long,
+        value_name = "PATH",
+        help_heading = "Dataset-specific Options",
+        num_args(1..),
+        verbatim_doc_comment
+    )]
+    pub transfers: Option<Vec<String>>,
+
+    /// Number of replayed erc20_holders balances to verify with balanceOf
+    #[arg(long, value_name = "N", help_heading = "Dataset-specific Options")]
+    pub spot_check: Option<u64>,
+
+    /// Use geth's flatCallTracer for geth_traces, giving parity trace addresses
+    #[arg(long, help_heading = "Dataset-specific Options")]
+    pub flat_call_tracer: bool,
+
+    /// Tracer for geth_custom_traces, a javascript file or native
+    /// tracer name (e.g. prestateTracer, 4byteTracer)
+    #[arg(
+        long,
+        value_name = "TRACER",
+        help_heading = "Dataset-specific Options",
+        verbatim_doc_comment
+    )]
+    pub tracer: Option<String>,
+
+    /// Config given to the tracer, as json or a path to a json file
+    #[arg(long, value_name = "CONFIG", help_heading = "Dataset-specific Options")]
+    pub tracer_config: Option<String>,
+
+    /// Columns to extract from tracer results, in format
+    /// COLUMN=JSON.PATH (e.g. gas=$.gas to=$.calls[0].to),
+    /// results are otherwise stored as json in the result column
+    #[arg(
+        long,
+        value_name = "COLUMN=PATH",
+        help_heading = "Dataset-specific Options",
+        num_args(1..),
+        verbatim_doc_comment
+    )]
+    pub tracer_columns: Option<Vec<String>>,
+
+    /// JSON file of state overrides and block overrides for
+    /// eth_calls and trace_calls, in format
+    /// {"stateOverrides": {ADDRESS: {"balance", "nonce", "code",
+    /// "state" or "stateDiff"}}, "blockOverrides": {"time", "baseFee"}}
+    #[arg(
+        long,
+        value_name = "PATH",
+        help_heading = "Dataset-specific Options",
+        verbatim_doc_comment
+    )]
+    pub call_overrides: Option<String>,
+
+    /// JSON file of ordered bundles for bundle_simulations, each a
+    /// list of raw signed transactions or unsigned call objects
+    #[arg(
+        long,
+        value_name = "PATH",
+        help_heading = "Dataset-specific Options",
+        verbatim_doc_comment
+    )]
+    pub bundles: Option<String>,
+
+    /// Simulate bundles with eth_callBundle instead of trace_callMany
+    #[arg(long, help_heading = "Dataset-specific Options")]
+    pub call_bundle: bool,
+
     /// Blocks per request (eth_getLogs)
     #[arg(
         long,
@@ -248,6 +360,16 @@ pub struct Args {
         help_heading = "Dataset-specific Options"
     )]
     pub inner_request_size: u64,
+
+    /// Blocks per request when filtering by address (trace_filter),
+    /// [default: whole chunk]
+    #[arg(
+        long,
+        value_name = "BLOCKS",
+        help_heading = "Dataset-specific Options",
+        verbatim_doc_comment
+    )]
+    pub filter_request_size: Option<u64>,
 }
 
 impl Args {
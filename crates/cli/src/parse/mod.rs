@@ -7,6 +7,8 @@ mod partitions;
 mod query;
 pub(crate) mod schemas;
 mod source;
+mod stream;
 
 pub use args::*;
 use schemas::*;
+pub(crate) use stream::parse_stream_options;
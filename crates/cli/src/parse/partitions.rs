@@ -5,7 +5,7 @@ use super::{
 use crate::args::Args;
 use cryo_freeze::{
     AddressChunk, CallDataChunk, Datatype, Dim, Fetcher, ParseError, Partition, PartitionLabels,
-    SlotChunk, Table, TimeDimension, TopicChunk, TransactionChunk,
+    SlotChunk, StorageLayout, Table, TimeDimension, TopicChunk, TransactionChunk,
 };
 use ethers::prelude::*;
 use std::{collections::HashMap, str::FromStr, sync::Arc};
@@ -27,8 +27,11 @@ pub(crate) async fn parse_partitions<P: JsonRpcClient>(
     let call_data_labels = None;
     let (address_labels, addresses) = parse_address_chunks(&args.address, "address")?;
     let (contract_labels, contracts) = parse_address_chunks(&args.contract, "contract_address")?;
+    let (from_address_labels, from_addresses) =
+        parse_address_chunks(&args.from_address, "from_address")?;
     let (to_address_labels, to_addresses) = parse_address_chunks(&args.to_address, "to_address")?;
-    let (slot_labels, slots) = parse_slot_chunks(&args.slot, "slot")?;
+    let (slot_labels, slots) =
+        parse_slot_chunks(&resolve_mapping_slots(&args.slot, schemas)?, "slot")?;
     let (topic0_labels, topic0s) = parse_topic(&args.topic0, "topic0")?;
     let (topic1_labels, topic1s) = parse_topic(&args.topic1, "topic1")?;
     let (topic2_labels, topic2s) = parse_topic(&args.topic2, "topic2")?;
@@ -48,6 +51,7 @@ pub(crate) async fn parse_partitions<P: JsonRpcClient>(
         transactions,
         addresses,
         contracts,
+        from_addresses,
         to_addresses,
         slots,
         call_datas,
@@ -62,6 +66,7 @@ pub(crate) async fn parse_partitions<P: JsonRpcClient>(
         call_data_labels,
         address_labels,
         contract_labels,
+        from_address_labels,
         to_address_labels,
         slot_labels,
         topic0_labels,
@@ -174,6 +179,30 @@ pub(crate) fn parse_address_chunks(
     }
 }
 
+/// convert mapping slot expressions such as `balances[0x..]` into hex slots
+fn resolve_mapping_slots(
+    input: &Option<Vec<String>>,
+    schemas: &HashMap<Datatype, Table>,
+) -> Result<Option<Vec<String>>, ParseError> {
+    let slots = match input {
+        Some(slots) if slots.iter().any(|slot| slot.contains('[')) => slots,
+        _ => return Ok(input.clone()),
+    };
+    let decoder = schemas.values().find_map(|schema| schema.storage_decoder.clone());
+    let layouts = decoder.as_ref().map(|x| x.all_layouts()).unwrap_or_default();
+    let mut resolved = Vec::new();
+    for slot in slots.iter() {
+        if slot.contains('[') {
+            let (slot, _) =
+                StorageLayout::mapping_slot(&layouts, slot).map_err(ParseError::ParseError)?;
+            resolved.push(format!("{:?}", slot));
+        } else {
+            resolved.push(slot.clone())
+        }
+    }
+    Ok(Some(resolved))
+}
+
 pub(crate) fn parse_slot_chunks(
     input: &Option<Vec<String>>,
     default_column: &str,
@@ -1,8 +1,11 @@
 use super::{parse_schemas, partitions};
 use crate::args::Args;
-use cryo_freeze::{Dim, Fetcher, ParseError, Query, QueryLabels, Schemas};
+use cryo_freeze::{
+    BundleTransaction, CallOverrides, CustomTracer, Dim, Fetcher, ParseError, PriceSource, Query,
+    QueryLabels, Schemas, TransferFiles,
+};
 use ethers::prelude::*;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 pub(crate) async fn parse_query<P: JsonRpcClient>(
     args: &Args,
@@ -19,6 +22,29 @@ pub(crate) async fn parse_query<P: JsonRpcClient>(
         partitions::parse_partitions(args, fetcher, &schemas).await?;
     let datatypes = cryo_freeze::cluster_datatypes(schemas.keys().cloned().collect());
     let labels = QueryLabels { align: args.align, reorg_buffer: args.reorg_buffer };
+    let price_sources = match &args.price_sources {
+        Some(path) => Some(PriceSource::load_file(path)?),
+        None => None,
+    };
+    let token_ids = parse_token_ids(args)?;
+    let custom_tracer = match &args.tracer {
+        Some(tracer) => Some(CustomTracer::new(
+            tracer,
+            args.tracer_config.as_deref(),
+            args.tracer_columns.as_deref(),
+        )?),
+        None => None,
+    };
+    let call_overrides = match &args.call_overrides {
+        Some(path) => Some(CallOverrides::load_file(path)?),
+        None => None,
+    };
+    let bundles = match &args.bundles {
+        Some(path) => Some(BundleTransaction::load_file(path)?),
+        None => None,
+    };
+    let transfer_files =
+        args.transfers.as_ref().map(|x| TransferFiles::new(x.iter().map(PathBuf::from).collect()));
     Ok(Query {
         datatypes,
         schemas,
@@ -26,10 +52,76 @@ pub(crate) async fn parse_query<P: JsonRpcClient>(
         partitions,
         partitioned_by,
         exclude_failed: args.exclude_failed,
+        price_sources,
+        token_ids,
+        transfer_files,
+        spot_check: args.spot_check,
+        flat_call_tracer: args.flat_call_tracer,
+        custom_tracer,
+        call_overrides,
+        bundles,
+        call_bundle: args.call_bundle,
         labels,
     })
 }
 
+/// maximum number of token ids, each of which is queried in every chunk
+const MAX_TOKEN_IDS: u64 = 1_000_000;
+
+fn parse_token_ids(args: &Args) -> Result<Option<Vec<U256>>, ParseError> {
+    match &args.token_ids {
+        Some(raw_token_ids) => Ok(Some(parse_token_id_ranges(raw_token_ids)?)),
+        None => Ok(None),
+    }
+}
+
+fn parse_token_id_ranges(raw_token_ids: &[String]) -> Result<Vec<U256>, ParseError> {
+    let mut token_ids = Vec::new();
+    for raw in raw_token_ids.iter() {
+        match raw.split(':').collect::<Vec<_>>().as_slice() {
+            [token_id] => token_ids.push(parse_token_id(token_id)?),
+            [start, end] => {
+                let (start, end) = (parse_token_id(start)?, parse_token_id(end)?);
+                if end < start {
+                    return Err(ParseError::ParseError(
+                        "end token id should not be less than start token id".to_string(),
+                    ))
+                }
+                let remaining = MAX_TOKEN_IDS.saturating_sub(token_ids.len() as u64);
+                if end - start >= U256::from(remaining) {
+                    return Err(ParseError::ParseError(format!(
+                        "too many token ids, at most {} are allowed",
+                        MAX_TOKEN_IDS
+                    )))
+                }
+                let mut token_id = start;
+                while token_id <= end {
+                    token_ids.push(token_id);
+                    if token_id == U256::MAX {
+                        break
+                    }
+                    token_id += U256::one();
+                }
+            }
+            _ => {
+                return Err(ParseError::ParseError(
+                    "token ids must be in format token_id or start:end".to_string(),
+                ))
+            }
+        }
+    }
+    Ok(token_ids)
+}
+
+fn parse_token_id(raw: &str) -> Result<U256, ParseError> {
+    let raw = raw.replace('_', "");
+    let token_id = match raw.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).ok(),
+        None => U256::from_dec_str(&raw).ok(),
+    };
+    token_id.ok_or(ParseError::ParseError(format!("could not parse token id {}", raw)))
+}
+
 fn find_arg_aliases(args: &Args, schemas: &Schemas) -> Vec<(Dim, Dim)> {
     // does not currently handle optional args, just required args
     let mut swaps = Vec::new();
@@ -62,6 +154,7 @@ impl DimIsNone for Args {
             Dim::BlockNumber => self.blocks.is_some(),
             Dim::TransactionHash => self.txs.is_some(),
             Dim::Address => self.address.is_some(),
+            Dim::FromAddress => self.from_address.is_some(),
             Dim::ToAddress => self.to_address.is_some(),
             Dim::Contract => self.contract.is_some(),
             Dim::CallData => self.call_data.is_some(),
@@ -78,6 +171,7 @@ impl DimIsNone for Args {
             Dim::BlockNumber => self.blocks.is_none(),
             Dim::TransactionHash => self.txs.is_none(),
             Dim::Address => self.address.is_none(),
+            Dim::FromAddress => self.from_address.is_none(),
             Dim::ToAddress => self.to_address.is_none(),
             Dim::Contract => self.contract.is_none(),
             Dim::CallData => self.call_data.is_none(),
@@ -117,3 +211,21 @@ fn apply_arg_aliases(args: &Args, arg_aliases: Vec<(Dim, Dim)>) -> Result<Args,
     }
     Ok(args)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_token_id_ranges() {
+        let parse = |raw: &[&str]| {
+            parse_token_id_ranges(&raw.iter().map(|x| x.to_string()).collect::<Vec<_>>())
+        };
+        let token_ids = parse(&["7", "0x10:0x12"]).unwrap();
+        assert_eq!(token_ids, vec![7.into(), 16.into(), 17.into(), 18.into()]);
+        assert_eq!(parse(&["0:999999"]).unwrap().len(), 1_000_000);
+        assert!(parse(&["1", "0:999999"]).is_err());
+        assert!(parse(&["0:18446744073709551616"]).is_err());
+        assert!(parse(&["2:1"]).is_err());
+    }
+}
@@ -1,11 +1,15 @@
 use std::collections::{HashMap, HashSet};
 
-use cryo_freeze::{ColumnEncoding, Datatype, FileFormat, MultiDatatype, ParseError, Table};
+use cryo_freeze::{
+    ColumnEncoding, Datatype, FileFormat, FunctionDecoder, MultiDatatype, ParseError,
+    SignatureDatabase, SignatureKind, StorageDecoder, StorageLayout, Table,
+};
 
 use super::file_output;
 use crate::args::Args;
 use cryo_freeze::U256Type;
-use std::str::FromStr;
+use ethers::prelude::*;
+use std::{str::FromStr, sync::Arc};
 
 fn parse_datatypes(raw_inputs: &Vec<String>) -> Result<Vec<Datatype>, ParseError> {
     let mut datatypes = Vec::new();
@@ -34,6 +38,9 @@ pub(crate) fn parse_schemas(args: &Args) -> Result<HashMap<Datatype, Table>, Par
         true => ColumnEncoding::Hex,
         false => ColumnEncoding::Binary,
     };
+    let function_decoder = parse_function_decoder(args)?;
+    let signature_database = parse_signature_database(args)?.map(Arc::new);
+    let storage_decoder = parse_storage_decoder(args)?.map(Arc::new);
 
     // create schemas
     let schemas: Result<HashMap<Datatype, Table>, ParseError> = datatypes
@@ -48,6 +55,9 @@ pub(crate) fn parse_schemas(args: &Args) -> Result<HashMap<Datatype, Table>, Par
                     &args.columns,
                     sort[datatype].clone(),
                     None,
+                    function_decoder.clone(),
+                    signature_database.clone(),
+                    storage_decoder.clone(),
                 )
                 .map(|schema| (*datatype, schema))
                 .map_err(|e| {
@@ -72,6 +82,85 @@ pub(crate) fn parse_schemas(args: &Args) -> Result<HashMap<Datatype, Table>, Par
     schemas
 }
 
+fn parse_function_decoder(args: &Args) -> Result<Option<FunctionDecoder>, ParseError> {
+    let decoder = match (&args.function_signature, &args.abi) {
+        (Some(_), Some(_)) => {
+            return Err(ParseError::ParseError(
+                "specify only one of --function-signature or --abi".to_string(),
+            ))
+        }
+        (Some(signatures), None) => FunctionDecoder::new(signatures.clone()),
+        (None, Some(path)) => {
+            let abi_json = std::fs::read_to_string(path)
+                .map_err(|_| ParseError::ParseError(format!("could not read abi file {}", path)))?;
+            FunctionDecoder::from_abi_json(&abi_json)
+        }
+        (None, None) => return Ok(None),
+    };
+    decoder.map(Some).map_err(ParseError::ParseError)
+}
+
+fn parse_signature_database(args: &Args) -> Result<Option<SignatureDatabase>, ParseError> {
+    let paths = match &args.signatures {
+        Some(paths) => paths,
+        None => return Ok(None),
+    };
+    let mut database = SignatureDatabase::default();
+    for raw in paths.iter() {
+        let (kind, path) = match raw.split_once('=') {
+            Some(("function", path)) => (Some(SignatureKind::Function), path),
+            Some(("event", path)) => (Some(SignatureKind::Event), path),
+            _ => (None, raw.as_str()),
+        };
+        database.load_file(path, kind).map_err(ParseError::ParseError)?;
+    }
+    if database.functions.is_empty() && database.events.is_empty() {
+        return Err(ParseError::ParseError("no signatures found in signature files".to_string()))
+    }
+    Ok(Some(database))
+}
+
+fn parse_storage_decoder(args: &Args) -> Result<Option<StorageDecoder>, ParseError> {
+    let slot_expressions: Vec<&String> =
+        args.slot.iter().flatten().filter(|slot| slot.contains('[')).collect();
+    if args.storage_layout.is_none() && args.mapping_keys.is_none() && slot_expressions.is_empty() {
+        return Ok(None)
+    }
+    let mut decoder = StorageDecoder::default();
+    for raw in args.storage_layout.iter().flatten() {
+        let (address, path) = match raw.split_once('=') {
+            Some((address, path)) => (Some(address), path),
+            None => (None, raw.as_str()),
+        };
+        let json = std::fs::read_to_string(path).map_err(|_| {
+            ParseError::ParseError(format!("could not read storage layout {}", path))
+        })?;
+        let layout = StorageLayout::from_json(&json).map_err(ParseError::ParseError)?;
+        match address {
+            Some(address) => {
+                let address = H160::from_str(address).map_err(|_| {
+                    ParseError::ParseError(format!("invalid storage layout address {}", address))
+                })?;
+                decoder.layouts.insert(address, layout);
+            }
+            None => decoder.default_layout = Some(layout),
+        }
+    }
+    if let Some(mapping_keys) = &args.mapping_keys {
+        decoder.add_keys(mapping_keys).map_err(ParseError::ParseError)?;
+    }
+    for expression in slot_expressions.into_iter() {
+        let (_, keys) = StorageLayout::mapping_slot(&decoder.all_layouts(), expression)
+            .map_err(ParseError::ParseError)?;
+        for key in keys.into_iter() {
+            if !decoder.keys.contains(&key) {
+                decoder.keys.push(key)
+            }
+        }
+    }
+    Ok(Some(decoder))
+}
+
 fn parse_u256_types(args: &Args) -> Result<HashSet<U256Type>, ParseError> {
     if let Some(raw_u256_types) = args.u256_types.clone() {
         let mut u256_types: HashSet<U256Type> = HashSet::new();
@@ -44,6 +44,7 @@ pub(crate) async fn parse_source(args: &Args) -> Result<Source, ParseError> {
         fetcher: Arc::new(fetcher),
         chain_id,
         inner_request_size: args.inner_request_size,
+        filter_request_size: args.filter_request_size,
         max_concurrent_chunks,
         rpc_url,
         labels: SourceLabels {
@@ -0,0 +1,19 @@
+use crate::args::Args;
+use cryo_freeze::{ParseError, StreamOptions};
+use std::env;
+
+pub(crate) fn parse_stream_options(args: &Args) -> Result<StreamOptions, ParseError> {
+    let ws_url = match &args.ws_rpc {
+        Some(url) => url.clone(),
+        None => env::var("ETH_WS_URL").map_err(|_| {
+            ParseError::ParseError("must provide --ws-rpc or set ETH_WS_URL".to_string())
+        })?,
+    };
+    let ws_url =
+        if ws_url.starts_with("ws") { ws_url } else { "ws://".to_string() + ws_url.as_str() };
+    Ok(StreamOptions {
+        ws_url,
+        rotate_interval: args.rotate_interval,
+        duration: args.stream_duration,
+    })
+}
@@ -2,7 +2,7 @@ use crate::{args, parse, remember};
 use clap_cryo::Parser;
 use color_print::cstr;
 use colored::Colorize;
-use cryo_freeze::{CollectError, ExecutionEnv, FreezeSummary};
+use cryo_freeze::{CollectError, Datatype, ExecutionEnv, FreezeSummary};
 use std::{sync::Arc, time::SystemTime};
 
 /// run cli
@@ -51,6 +51,14 @@ pub async fn run(args: args::Args) -> Result<Option<FreezeSummary>, CollectError
     let source = Arc::new(source);
     let env = ExecutionEnv { t_start_parse, ..env };
     let env = env.set_start_time();
+
+    // streamed datasets are collected live instead of by partition
+    if query.schemas.contains_key(&Datatype::PendingTransactions) {
+        let options = parse::parse_stream_options(&args)?;
+        cryo_freeze::stream(&query, source, &sink, &env, &options).await?;
+        return Ok(None)
+    }
+
     cryo_freeze::freeze(&query, &source, &sink, &env).await
 }
 
@@ -0,0 +1,214 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+use std::{collections::HashMap, sync::Mutex};
+
+/// columns for address transactions, the transactions touching each address
+///
+/// requires a node with the Otterscan api (ots_ namespace), pages through each chunk of
+/// blocks per address, or through ranges of --inner-request-size blocks if given
+#[cryo_to_df::to_df(Datatype::AddressTransactions)]
+#[derive(Default)]
+pub struct AddressTransactions {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    transaction_index: Vec<u32>,
+    transaction_hash: Vec<Vec<u8>>,
+    address: Vec<Vec<u8>>,
+    from_address: Vec<Vec<u8>>,
+    to_address: Vec<Option<Vec<u8>>>,
+    value: Vec<U256>,
+    gas_used: Vec<Option<u64>>,
+    success: Vec<Option<bool>>,
+    contract_creator: Vec<Option<Vec<u8>>>,
+    creation_transaction_hash: Vec<Option<Vec<u8>>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for AddressTransactions {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "transaction_hash",
+            "address",
+            "from_address",
+            "to_address",
+            "value",
+            "gas_used",
+            "success",
+            "chain_id",
+        ])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["address", "block_number", "transaction_index"])
+    }
+
+    fn required_parameters() -> Vec<Dim> {
+        vec![Dim::Address]
+    }
+
+    fn use_block_ranges() -> bool {
+        true
+    }
+
+    fn block_range_parameters() -> Vec<Dim> {
+        vec![Dim::Address]
+    }
+}
+
+/// number of transactions requested per ots_searchTransactionsAfter page
+const OTS_PAGE_SIZE: u64 = 25;
+
+type AddressTxs = (Vec<u8>, Vec<(Transaction, TransactionReceipt)>, Option<OtsContractCreator>);
+
+lazy_static::lazy_static! {
+    /// contract creators already fetched, keyed by chain id and address
+    static ref CONTRACT_CREATORS: Mutex<HashMap<(u64, H160), Option<OtsContractCreator>>> =
+        Mutex::new(HashMap::new());
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for AddressTransactions {
+    type Response = AddressTxs;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::AddressTransactions)?;
+        let address = request.ethers_address()?;
+        let (start, end) = request.block_range()?;
+
+        let fetch_page =
+            |cursor| source.fetcher.ots_search_transactions_after(address, cursor, OTS_PAGE_SIZE);
+        let txs = page_transactions(start, end, fetch_page).await?;
+
+        let creator = if schema.has_column("contract_creator") ||
+            schema.has_column("creation_transaction_hash")
+        {
+            get_contract_creator(address, &source).await?
+        } else {
+            None
+        };
+        Ok((address.as_bytes().to_vec(), txs, creator))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::AddressTransactions)?;
+        let (address, txs, creator) = response;
+        let contract_creator = creator.as_ref().map(|x| x.creator.as_bytes().to_vec());
+        let creation_transaction_hash = creator.as_ref().map(|x| x.hash.as_bytes().to_vec());
+        for (tx, receipt) in txs.into_iter() {
+            let (block_number, transaction_index) = match (tx.block_number, tx.transaction_index) {
+                (Some(block_number), Some(transaction_index)) => {
+                    (block_number.as_u32(), transaction_index.as_u32())
+                }
+                _ => return Err(err("transaction without block")),
+            };
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, block_number);
+            store!(schema, columns, transaction_index, transaction_index);
+            store!(schema, columns, transaction_hash, tx.hash.as_bytes().to_vec());
+            store!(schema, columns, address, address.clone());
+            store!(schema, columns, from_address, tx.from.as_bytes().to_vec());
+            store!(schema, columns, to_address, tx.to.map(|x| x.as_bytes().to_vec()));
+            store!(schema, columns, value, tx.value);
+            store!(schema, columns, gas_used, receipt.gas_used.map(|x| x.as_u64()));
+            store!(schema, columns, success, receipt.status.map(|x| x.as_u64() == 1));
+            store!(schema, columns, contract_creator, contract_creator.clone());
+            store!(schema, columns, creation_transaction_hash, creation_transaction_hash.clone());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for AddressTransactions {
+    type Response = ();
+}
+
+/// page through transactions of blocks start to end, one page after another
+async fn page_transactions<F, Fut>(
+    start: u64,
+    end: u64,
+    fetch_page: F,
+) -> R<Vec<(Transaction, TransactionReceipt)>>
+where
+    F: Fn(u64) -> Fut,
+    Fut: std::future::Future<Output = R<OtsTransactionsPage>>,
+{
+    // pages contain whole blocks, so page forward from the last block of each page
+    let mut txs = Vec::new();
+    let mut cursor = start.saturating_sub(1);
+    loop {
+        let page = fetch_page(cursor).await?;
+        let last_block = match page.txs.iter().filter_map(|tx| tx.block_number).max() {
+            Some(last_block) => last_block.as_u64(),
+            None => break,
+        };
+        for (tx, receipt) in page.txs.into_iter().zip(page.receipts) {
+            if tx.block_number.map(|x| x.as_u64() <= end).unwrap_or(false) {
+                txs.push((tx, receipt))
+            }
+        }
+        if last_block >= end || page.first_page {
+            break
+        }
+        cursor = last_block;
+    }
+    Ok(txs)
+}
+
+/// get creator of contract, fetching it only once per address
+async fn get_contract_creator(address: H160, source: &Source) -> R<Option<OtsContractCreator>> {
+    let key = (source.chain_id, address);
+    let cached = match CONTRACT_CREATORS.lock() {
+        Ok(cache) => cache.get(&key).cloned(),
+        Err(poisoned) => poisoned.into_inner().get(&key).cloned(),
+    };
+    if let Some(creator) = cached {
+        return Ok(creator)
+    }
+    let creator = source.fetcher.ots_get_contract_creator(address).await?;
+    match CONTRACT_CREATORS.lock() {
+        Ok(mut cache) => cache.insert(key, creator.clone()),
+        Err(poisoned) => poisoned.into_inner().insert(key, creator.clone()),
+    };
+    Ok(creator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_page_transactions() {
+        let tx = |block_number: u64| {
+            let tx = Transaction { block_number: Some(block_number.into()), ..Default::default() };
+            (tx, TransactionReceipt::default())
+        };
+        let page = |blocks: &[u64], first_page: bool| {
+            let (txs, receipts) = blocks.iter().map(|block| tx(*block)).unzip();
+            OtsTransactionsPage { txs, receipts, first_page, last_page: false }
+        };
+        let cursors = std::sync::Mutex::new(Vec::new());
+        let fetch_page = |cursor| {
+            cursors.lock().unwrap().push(cursor);
+            let page = match cursor {
+                9 => page(&[12, 11, 10], false),
+                12 => page(&[20, 15], false),
+                _ => page(&[30], true),
+            };
+            async move { Ok(page) }
+        };
+        let txs = page_transactions(10, 18, fetch_page).await.unwrap();
+        let blocks: Vec<_> = txs.iter().filter_map(|(tx, _)| tx.block_number).collect();
+        assert_eq!(blocks, vec![12.into(), 11.into(), 10.into(), 15.into()]);
+        assert_eq!(*cursors.lock().unwrap(), vec![9, 12]);
+
+        // paging ends at the most recent page
+        let txs = page_transactions(10, 40, fetch_page).await.unwrap();
+        assert_eq!(txs.len(), 6);
+        assert_eq!(*cursors.lock().unwrap(), vec![9, 12, 9, 12, 20]);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+
+/// columns for block rewards
+#[cryo_to_df::to_df(Datatype::BlockRewards)]
+#[derive(Default)]
+pub struct BlockRewards {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    reward_type: Vec<String>,
+    transaction_index: Vec<Option<u32>>,
+    transaction_hash: Vec<Option<Vec<u8>>>,
+    from_address: Vec<Option<Vec<u8>>>,
+    to_address: Vec<Vec<u8>>,
+    value: Vec<U256>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for BlockRewards {
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "reward_type", "transaction_index", "to_address"])
+    }
+}
+
+type BlockRewardsResponse = (Block<Transaction>, Vec<TransactionReceipt>, Vec<Trace>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for BlockRewards {
+    type Response = BlockRewardsResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let block_number = request.block_number()?;
+        let block = source
+            .fetcher
+            .get_block_with_txs(block_number)
+            .await?
+            .ok_or(CollectError::CollectError("block not found".to_string()))?;
+        let receipts = source.fetcher.get_block_receipts(block_number).await?;
+        let traces = source.fetcher.trace_block(block_number.into()).await?;
+        Ok((block, receipts, traces))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::BlockRewards)?;
+        process_block_rewards(response, columns, schema)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for BlockRewards {
+    type Response = ();
+}
+
+/// reward type, transaction index, transaction hash, from address, to address, value
+type RewardRow = (String, Option<usize>, Option<H256>, Option<H160>, H160, U256);
+
+fn process_block_rewards(
+    response: BlockRewardsResponse,
+    columns: &mut BlockRewards,
+    schema: &Table,
+) -> R<()> {
+    let (block, receipts, traces) = response;
+    let block_number =
+        block.number.ok_or(CollectError::CollectError("block has no number".to_string()))?;
+    let miner =
+        block.author.ok_or(CollectError::CollectError("block has no author".to_string()))?;
+    let mut rows: Vec<RewardRow> = Vec::new();
+
+    // static block and uncle rewards, as reported by reward traces
+    for trace in traces.iter() {
+        if let Action::Reward(action) = &trace.action {
+            let reward_type = traces::reward_type_to_string(&action.reward_type);
+            rows.push((reward_type, None, None, None, action.author, action.value));
+        }
+    }
+
+    // priority fees paid by all transactions of block
+    let priority_fees = get_priority_fees(&block, &receipts)?;
+    rows.push(("priority_fees".to_string(), None, None, None, miner, priority_fees));
+
+    // direct transfers to the coinbase from successful calls
+    let traces = traces::filter_failed_traces(traces);
+    for trace in traces.iter() {
+        if let (Action::Call(action), Some(_)) = (&trace.action, trace.transaction_position) {
+            if action.to == miner && action.from != miner && !action.value.is_zero() {
+                rows.push((
+                    "coinbase_transfer".to_string(),
+                    trace.transaction_position,
+                    trace.transaction_hash,
+                    Some(action.from),
+                    miner,
+                    action.value,
+                ));
+            }
+        }
+    }
+
+    // payment from the builder to the proposer in the final transaction of block
+    let last_position = traces.iter().filter_map(|trace| trace.transaction_position).max();
+    for trace in traces.iter() {
+        if trace.transaction_position != last_position || !trace.trace_address.is_empty() {
+            continue
+        }
+        if let Action::Call(action) = &trace.action {
+            if action.from == miner && action.to != miner && !action.value.is_zero() {
+                rows.push((
+                    "builder_payment".to_string(),
+                    trace.transaction_position,
+                    trace.transaction_hash,
+                    Some(miner),
+                    action.to,
+                    action.value,
+                ));
+            }
+        }
+    }
+
+    for (reward_type, transaction_index, transaction_hash, from_address, to_address, value) in
+        rows.into_iter()
+    {
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, block_number.as_u32());
+        store!(schema, columns, reward_type, reward_type);
+        store!(schema, columns, transaction_index, transaction_index.map(|x| x as u32));
+        store!(schema, columns, transaction_hash, transaction_hash.map(|x| x.as_bytes().to_vec()));
+        store!(schema, columns, from_address, from_address.map(|x| x.as_bytes().to_vec()));
+        store!(schema, columns, to_address, to_address.as_bytes().to_vec());
+        store!(schema, columns, value, value);
+    }
+
+    Ok(())
+}
+
+fn get_priority_fees(block: &Block<Transaction>, receipts: &[TransactionReceipt]) -> R<U256> {
+    if block.transactions.len() != receipts.len() {
+        return Err(err("number of receipts does not match number of transactions"))
+    }
+    let base_fee = block.base_fee_per_gas.unwrap_or_default();
+    let mut total = U256::zero();
+    for (tx, receipt) in block.transactions.iter().zip(receipts.iter()) {
+        let gas_used =
+            receipt.gas_used.ok_or(CollectError::CollectError("no gas_used for tx".to_string()))?;
+        let gas_price = match (receipt.effective_gas_price, tx.gas_price) {
+            (Some(gas_price), _) => gas_price,
+            (None, Some(gas_price)) => gas_price,
+            (None, None) => return Err(err("no gas price for tx")),
+        };
+        total += gas_used * gas_price.saturating_sub(base_fee);
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(x: u64) -> H160 {
+        H160::from_low_u64_be(x)
+    }
+
+    fn transaction(index: u64, gas_price: u64) -> (Transaction, TransactionReceipt) {
+        let tx = Transaction {
+            hash: H256::from_low_u64_be(index + 1),
+            transaction_index: Some(index.into()),
+            gas_price: Some(gas_price.into()),
+            ..Default::default()
+        };
+        let receipt = TransactionReceipt {
+            transaction_index: index.into(),
+            gas_used: Some(21_000.into()),
+            effective_gas_price: Some(gas_price.into()),
+            ..Default::default()
+        };
+        (tx, receipt)
+    }
+
+    fn call_trace(from: H160, to: H160, value: u64, position: usize) -> Trace {
+        let json = format!(
+            r#"{{"action": {{"callType": "call", "from": "{:?}", "gas": "0x0", "input": "0x", "to": "{:?}", "value": "{:#x}"}}, "blockHash": "0x0000000000000000000000000000000000000000000000000000000000000000", "blockNumber": 1, "result": {{"gasUsed": "0x0", "output": "0x"}}, "subtraces": 0, "traceAddress": [], "transactionHash": "{:?}", "transactionPosition": {}, "type": "call"}}"#,
+            from,
+            to,
+            value,
+            H256::from_low_u64_be(position as u64 + 1),
+            position
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn block(base_fee: Option<u64>, transactions: Vec<Transaction>) -> Block<Transaction> {
+        Block {
+            number: Some(1.into()),
+            author: Some(address(1)),
+            base_fee_per_gas: base_fee.map(U256::from),
+            transactions,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_priority_fees() {
+        let (txs, receipts): (Vec<_>, Vec<_>) =
+            vec![transaction(0, 30), transaction(1, 12)].into_iter().unzip();
+
+        // post-london, only the part of the gas price above the base fee is paid to the miner
+        let fees = get_priority_fees(&block(Some(10), txs.clone()), &receipts).unwrap();
+        assert_eq!(fees, U256::from(21_000 * (20 + 2)));
+
+        // pre-london blocks have no base fee, so the whole gas price is paid to the miner
+        let fees = get_priority_fees(&block(None, txs.clone()), &receipts).unwrap();
+        assert_eq!(fees, U256::from(21_000 * (30 + 12)));
+
+        assert!(get_priority_fees(&block(None, txs), &receipts[..1]).is_err());
+    }
+
+    #[test]
+    fn test_process_block_rewards() {
+        let (txs, receipts): (Vec<_>, Vec<_>) =
+            vec![transaction(0, 30), transaction(1, 10)].into_iter().unzip();
+        let reward: Trace = serde_json::from_str(
+            r#"{"action": {"author": "0x0000000000000000000000000000000000000001", "rewardType": "block", "value": "0x2"}, "blockHash": "0x0000000000000000000000000000000000000000000000000000000000000000", "blockNumber": 1, "result": null, "subtraces": 0, "traceAddress": [], "type": "reward"}"#,
+        )
+        .unwrap();
+        let traces = vec![
+            reward,
+            // a searcher tips the builder, then the builder pays the proposer
+            call_trace(address(2), address(1), 5, 0),
+            call_trace(address(1), address(3), 7, 1),
+        ];
+
+        let schema = test_schema(Datatype::BlockRewards, &[]);
+        let mut columns = BlockRewards::default();
+        let response = (block(Some(10), txs), receipts, traces);
+        process_block_rewards(response, &mut columns, &schema).unwrap();
+        assert_eq!(
+            columns.reward_type,
+            vec!["reward", "priority_fees", "coinbase_transfer", "builder_payment"]
+        );
+        assert_eq!(columns.transaction_index, vec![None, None, Some(0), Some(1)]);
+        assert_eq!(
+            columns.value,
+            vec![U256::from(2), U256::from(21_000 * 20), U256::from(5), U256::from(7)]
+        );
+        assert_eq!(columns.to_address[3], address(3).as_bytes().to_vec());
+    }
+}
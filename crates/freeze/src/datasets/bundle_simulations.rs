@@ -0,0 +1,346 @@
+use crate::*;
+use ethers::{
+    prelude::*,
+    types::transaction::eip2718::TypedTransaction,
+    utils::{keccak256, rlp},
+};
+use polars::prelude::*;
+
+/// columns for bundle simulations, each bundle simulated sequentially on top of a block
+///
+/// uses trace_callMany, or eth_callBundle if --call-bundle is given, in which case bundles
+/// must contain only signed transactions. trace_callMany gas includes intrinsic gas but is
+/// not reduced by refunds
+#[cryo_to_df::to_df(Datatype::BundleSimulations)]
+#[derive(Default)]
+pub struct BundleSimulations {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    bundle_index: Vec<u32>,
+    transaction_index: Vec<u32>,
+    transaction_hash: Vec<Option<Vec<u8>>>,
+    from_address: Vec<Option<Vec<u8>>>,
+    to_address: Vec<Option<Vec<u8>>>,
+    gas_used: Vec<Option<u64>>,
+    success: Vec<bool>,
+    error: Vec<Option<String>>,
+    coinbase_payment: Vec<Option<U256>>,
+    state_diff: Vec<Option<String>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for BundleSimulations {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "bundle_index",
+            "transaction_index",
+            "transaction_hash",
+            "from_address",
+            "to_address",
+            "gas_used",
+            "success",
+            "error",
+            "coinbase_payment",
+            "chain_id",
+        ])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "bundle_index", "transaction_index"])
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("latest".to_string())
+    }
+}
+
+/// transaction of a bundle, either raw signed or an unsigned call object
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(untagged)]
+pub enum BundleTransaction {
+    /// raw signed transaction
+    Raw(Bytes),
+    /// unsigned call object
+    Call(Box<TransactionRequest>),
+}
+
+impl BundleTransaction {
+    /// load ordered bundles from json file, ex: `[["0x02f8...", {"from": .., "to": ..}], ...]`
+    pub fn load_file(path: &str) -> Result<Vec<Vec<BundleTransaction>>, ParseError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|_| ParseError::ParseError(format!("could not read {}", path)))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| ParseError::ParseError(format!("invalid bundles: {}", e)))
+    }
+
+    /// transaction as call object, recovering the sender of signed transactions
+    fn to_call(&self) -> R<(Option<H256>, TypedTransaction)> {
+        match self {
+            BundleTransaction::Raw(raw) => {
+                let (mut transaction, signature) =
+                    TypedTransaction::decode_signed(&rlp::Rlp::new(raw))
+                        .map_err(|_| err("invalid signed transaction"))?;
+                let from = signature
+                    .recover(transaction.sighash())
+                    .map_err(|_| err("invalid transaction signature"))?;
+                transaction.set_from(from);
+                Ok((Some(H256::from(keccak256(raw))), transaction))
+            }
+            BundleTransaction::Call(call) => Ok((None, (**call).clone().into())),
+        }
+    }
+}
+
+/// simulation result of a transaction of a bundle
+#[derive(Default)]
+pub struct SimulatedTransaction {
+    transaction_hash: Option<Vec<u8>>,
+    from_address: Option<Vec<u8>>,
+    to_address: Option<Vec<u8>>,
+    gas_used: Option<u64>,
+    error: Option<String>,
+    coinbase_payment: Option<U256>,
+    state_diff: Option<String>,
+}
+
+type BlockBundles = (u32, Vec<Vec<SimulatedTransaction>>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for BundleSimulations {
+    type Response = BlockBundles;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let bundles = query.bundles.as_ref().ok_or(err("bundle_simulations requires bundles"))?;
+        let schema = query.schemas.get_schema(&Datatype::BundleSimulations)?;
+        let block_number = request.block_number()?;
+        let mut simulations = Vec::new();
+        if query.call_bundle {
+            for bundle in bundles.iter() {
+                simulations.push(call_bundle(bundle, block_number, &source).await?);
+            }
+        } else {
+            let block =
+                source.fetcher.get_block(block_number).await?.ok_or(err("could not find block"))?;
+            let coinbase = block.author.ok_or(err("could not find block author"))?;
+            // withdrawals and eip3860 init code costs were both activated by shanghai
+            let shanghai = block.withdrawals_root.is_some();
+            let include_state_diffs = schema.has_column("state_diff");
+            for bundle in bundles.iter() {
+                let simulation = trace_call_many(
+                    bundle,
+                    block_number,
+                    coinbase,
+                    shanghai,
+                    include_state_diffs,
+                    &source,
+                )
+                .await?;
+                simulations.push(simulation);
+            }
+        }
+        Ok((block_number as u32, simulations))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::BundleSimulations)?;
+        let (block_number, simulations) = response;
+        for (bundle_index, simulation) in simulations.into_iter().enumerate() {
+            for (transaction_index, tx) in simulation.into_iter().enumerate() {
+                columns.n_rows += 1;
+                store!(schema, columns, block_number, block_number);
+                store!(schema, columns, bundle_index, bundle_index as u32);
+                store!(schema, columns, transaction_index, transaction_index as u32);
+                store!(schema, columns, transaction_hash, tx.transaction_hash);
+                store!(schema, columns, from_address, tx.from_address);
+                store!(schema, columns, to_address, tx.to_address);
+                store!(schema, columns, gas_used, tx.gas_used);
+                store!(schema, columns, success, tx.error.is_none());
+                store!(schema, columns, error, tx.error);
+                store!(schema, columns, coinbase_payment, tx.coinbase_payment);
+                store!(schema, columns, state_diff, tx.state_diff);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for BundleSimulations {
+    type Response = ();
+}
+
+async fn trace_call_many(
+    bundle: &[BundleTransaction],
+    block_number: u64,
+    coinbase: H160,
+    shanghai: bool,
+    include_state_diffs: bool,
+    source: &Source,
+) -> R<Vec<SimulatedTransaction>> {
+    let mut hashes = Vec::new();
+    let mut calls = Vec::new();
+    for transaction in bundle.iter() {
+        let (hash, call) = transaction.to_call()?;
+        hashes.push(hash);
+        calls.push((call, vec![TraceType::Trace, TraceType::StateDiff]));
+    }
+    let addresses: Vec<_> =
+        calls.iter().map(|(call, _)| (call.from().cloned(), call.to_addr().cloned())).collect();
+    let intrinsic_gas: Vec<_> =
+        calls.iter().map(|(call, _)| intrinsic_gas(call, shanghai)).collect();
+    let traces = source.fetcher.trace_call_many(calls, block_number.into()).await?;
+
+    let mut simulations = Vec::new();
+    let transactions = hashes.into_iter().zip(addresses).zip(intrinsic_gas);
+    for (((hash, (from, to)), intrinsic_gas), trace) in transactions.zip(traces) {
+        // trace gas only covers execution, receipts also include intrinsic gas
+        let top_trace = trace.trace.as_ref().and_then(|traces| traces.first());
+        let gas_used = top_trace.and_then(|trace| match &trace.result {
+            Some(Res::Call(result)) => Some(result.gas_used.as_u64() + intrinsic_gas),
+            Some(Res::Create(result)) => Some(result.gas_used.as_u64() + intrinsic_gas),
+            _ => None,
+        });
+        let state_diff = match (&trace.state_diff, include_state_diffs) {
+            (Some(state_diff), true) => Some(
+                serde_json::to_string(state_diff)
+                    .map_err(|_| err("cannot serialize state diff"))?,
+            ),
+            _ => None,
+        };
+        simulations.push(SimulatedTransaction {
+            transaction_hash: hash.map(|x| x.as_bytes().to_vec()),
+            from_address: from.map(|x| x.as_bytes().to_vec()),
+            to_address: to.map(|x| x.as_bytes().to_vec()),
+            gas_used,
+            error: top_trace.and_then(|trace| trace.error.clone()),
+            coinbase_payment: trace.state_diff.as_ref().map(|x| balance_increase(x, &coinbase)),
+            state_diff,
+        })
+    }
+    Ok(simulations)
+}
+
+async fn call_bundle(
+    bundle: &[BundleTransaction],
+    block_number: u64,
+    source: &Source,
+) -> R<Vec<SimulatedTransaction>> {
+    let mut raw_transactions = Vec::new();
+    for transaction in bundle.iter() {
+        match transaction {
+            BundleTransaction::Raw(raw) => raw_transactions.push(raw.clone()),
+            BundleTransaction::Call(_) => {
+                return Err(err("eth_callBundle requires signed transactions"))
+            }
+        }
+    }
+    let response = source.fetcher.call_bundle(raw_transactions, block_number).await?;
+    let mut simulations = Vec::new();
+    for result in response.results.into_iter() {
+        let coinbase_payment =
+            U256::from_dec_str(&result.coinbase_diff).map_err(|_| err("invalid coinbase diff"))?;
+        simulations.push(SimulatedTransaction {
+            transaction_hash: Some(result.tx_hash.as_bytes().to_vec()),
+            from_address: Some(result.from_address.as_bytes().to_vec()),
+            to_address: result.to_address.map(|x| x.as_bytes().to_vec()),
+            gas_used: Some(result.gas_used),
+            error: result.error,
+            coinbase_payment: Some(coinbase_payment),
+            state_diff: None,
+        })
+    }
+    Ok(simulations)
+}
+
+/// gas charged before execution, for calldata, contract creation, and access lists
+///
+/// init code of creations is also charged per word from shanghai onwards
+fn intrinsic_gas(transaction: &TypedTransaction, shanghai: bool) -> u64 {
+    let data = transaction.data().map(|x| x.as_ref()).unwrap_or_default();
+    let zero_bytes = data.iter().filter(|b| **b == 0).count() as u64;
+    let mut gas = 21_000 + 4 * zero_bytes + 16 * (data.len() as u64 - zero_bytes);
+    if transaction.to().is_none() {
+        gas += 32_000;
+        if shanghai {
+            gas += 2 * (data.len() as u64).div_ceil(32);
+        }
+    }
+    if let Some(access_list) = transaction.access_list() {
+        for item in access_list.0.iter() {
+            gas += 2_400 + 1_900 * item.storage_keys.len() as u64;
+        }
+    }
+    gas
+}
+
+/// increase in balance of an address in a state diff
+fn balance_increase(state_diff: &StateDiff, address: &H160) -> U256 {
+    match state_diff.0.get(address).map(|diff| &diff.balance) {
+        Some(Diff::Born(balance)) => *balance,
+        Some(Diff::Changed(ChangedType { from, to })) => to.saturating_sub(*from),
+        _ => U256::zero(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_balance_increase() {
+        let coinbase = H160::from_low_u64_be(1);
+        let changed = ChangedType { from: U256::from(10), to: U256::from(25) };
+        let account = AccountDiff {
+            balance: Diff::Changed(changed),
+            nonce: Diff::Same,
+            code: Diff::Same,
+            storage: Default::default(),
+        };
+        let state_diff = StateDiff([(coinbase, account)].into_iter().collect());
+        assert_eq!(balance_increase(&state_diff, &coinbase), U256::from(15));
+        assert_eq!(balance_increase(&state_diff, &H160::zero()), U256::zero());
+    }
+
+    #[test]
+    fn test_bundle_transaction() {
+        let wallet: ethers::signers::LocalWallet =
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let transaction: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(H160::from_low_u64_be(2))
+            .data(vec![0, 1, 2])
+            .gas(50_000)
+            .nonce(0)
+            .max_fee_per_gas(100)
+            .max_priority_fee_per_gas(1)
+            .chain_id(1)
+            .into();
+        let signature = wallet.sign_transaction_sync(&transaction).unwrap();
+        let raw = transaction.rlp_signed(&signature);
+
+        let json = format!(
+            r#"[["{}", {{"from": "0x0000000000000000000000000000000000000001", "to": "0x0000000000000000000000000000000000000002"}}]]"#,
+            raw
+        );
+        let bundles: Vec<Vec<BundleTransaction>> = serde_json::from_str(&json).unwrap();
+        assert!(matches!(bundles[0][0], BundleTransaction::Raw(_)));
+        assert!(matches!(bundles[0][1], BundleTransaction::Call(_)));
+
+        let (hash, call) = bundles[0][0].to_call().unwrap();
+        assert_eq!(hash, Some(H256::from(keccak256(&raw))));
+        assert_eq!(call.from(), Some(&wallet.address()));
+        assert_eq!(call.to_addr(), Some(&H160::from_low_u64_be(2)));
+        assert_eq!(intrinsic_gas(&call, true), 21_000 + 4 + 16 * 2);
+
+        let (hash, call) = bundles[0][1].to_call().unwrap();
+        assert_eq!(hash, None);
+        assert_eq!(call.from(), Some(&H160::from_low_u64_be(1)));
+
+        // 33 bytes of init code span two words
+        let create: TypedTransaction = TransactionRequest::new().data(vec![1; 33]).into();
+        let pre_shanghai = 21_000 + 16 * 33 + 32_000;
+        assert_eq!(intrinsic_gas(&create, false), pre_shanghai);
+        assert_eq!(intrinsic_gas(&create, true), pre_shanghai + 2 * 2);
+    }
+}
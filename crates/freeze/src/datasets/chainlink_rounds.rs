@@ -0,0 +1,280 @@
+use crate::*;
+use ethers::{
+    abi::{decode, ParamType},
+    prelude::*,
+};
+use polars::prelude::*;
+use std::{collections::HashMap, sync::Mutex};
+
+/// columns for chainlink rounds
+#[cryo_to_df::to_df(Datatype::ChainlinkRounds)]
+#[derive(Default)]
+pub struct ChainlinkRounds {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    transaction_index: Vec<u32>,
+    log_index: Vec<u32>,
+    transaction_hash: Vec<Vec<u8>>,
+    aggregator: Vec<Vec<u8>>,
+    event: Vec<String>,
+    round_id: Vec<U256>,
+    answer: Vec<Option<f64>>,
+    raw_answer: Vec<Option<String>>,
+    updated_at: Vec<Option<u64>>,
+    started_at: Vec<Option<u64>>,
+    started_by: Vec<Option<Vec<u8>>>,
+    decimals: Vec<Option<u32>>,
+    description: Vec<Option<String>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for ChainlinkRounds {
+    fn aliases() -> Vec<&'static str> {
+        vec!["oracle_rounds"]
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "log_index"])
+    }
+
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::Contract]
+    }
+
+    fn use_block_ranges() -> bool {
+        true
+    }
+}
+
+/// decimals and description of a feed
+type FeedMetadata = (Option<u32>, Option<String>);
+
+type ChainlinkRoundsResponse = (Vec<Log>, HashMap<H160, FeedMetadata>);
+
+lazy_static::lazy_static! {
+    /// feed metadata fully resolved, keyed by chain id and aggregator address
+    static ref FEED_METADATA: Mutex<HashMap<(u64, H160), FeedMetadata>> =
+        Mutex::new(HashMap::new());
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for ChainlinkRounds {
+    type Response = ChainlinkRoundsResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let events = vec![Some(*EVENT_CHAINLINK_ANSWER_UPDATED), Some(*EVENT_CHAINLINK_NEW_ROUND)];
+        let topics = [Some(ValueOrArray::Array(events)), None, None, None];
+        let address = request.ethers_contract().ok().map(ValueOrArray::Value);
+        let filter = Filter { topics, address, ..request.ethers_log_filter()? };
+        let logs: Vec<Log> =
+            source.fetcher.get_logs(&filter).await?.into_iter().filter(is_round_event).collect();
+        let (_, end_block) = request.block_range()?;
+        let metadata = get_feed_metadata(&logs, &source, end_block.into()).await;
+        Ok((logs, metadata))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::ChainlinkRounds)?;
+        process_chainlink_rounds(response, columns, schema)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for ChainlinkRounds {
+    type Response = ChainlinkRoundsResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let logs = source.fetcher.get_transaction_logs(request.transaction_hash()?).await?;
+        let logs: Vec<Log> = logs.into_iter().filter(is_round_event).collect();
+        let block_number = match logs.first().and_then(|log| log.block_number) {
+            Some(block_number) => block_number.into(),
+            None => BlockNumber::Latest,
+        };
+        let metadata = get_feed_metadata(&logs, &source, block_number).await;
+        Ok((logs, metadata))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::ChainlinkRounds)?;
+        process_chainlink_rounds(response, columns, schema)
+    }
+}
+
+fn is_round_event(log: &Log) -> bool {
+    log.topics.len() == 3 &&
+        log.data.len() == 32 &&
+        (log.topics[0] == *EVENT_CHAINLINK_ANSWER_UPDATED ||
+            log.topics[0] == *EVENT_CHAINLINK_NEW_ROUND)
+}
+
+/// call decimals() and description() of each aggregator, failed calls give None
+///
+/// metadata is cached for the process once both calls of an aggregator succeed
+async fn get_feed_metadata(
+    logs: &[Log],
+    source: &Source,
+    block_number: BlockNumber,
+) -> HashMap<H160, FeedMetadata> {
+    let mut aggregators: Vec<H160> = logs.iter().map(|log| log.address).collect();
+    aggregators.sort();
+    aggregators.dedup();
+
+    let mut output = HashMap::new();
+    let mut futures = Vec::new();
+    {
+        let cache = match FEED_METADATA.lock() {
+            Ok(cache) => cache,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        for aggregator in aggregators.into_iter() {
+            if let Some(metadata) = cache.get(&(source.chain_id, aggregator)) {
+                output.insert(aggregator, metadata.clone());
+                continue
+            }
+            let fetcher = source.fetcher.clone();
+            futures.push(async move {
+                let (decimals, description) = futures::join!(
+                    fetcher.call2(aggregator, FUNCTION_ERC20_DECIMALS.clone(), block_number),
+                    fetcher.call2(aggregator, FUNCTION_CHAINLINK_DESCRIPTION.clone(), block_number),
+                );
+                let complete = decimals.is_ok() && description.is_ok();
+                let decimals = decimals.ok().and_then(|output| bytes_to_u32(output).ok());
+                let description = description.ok().and_then(|output| decode_description(&output));
+                (aggregator, (decimals, description), complete)
+            });
+        }
+    }
+
+    let fetched = futures::future::join_all(futures).await;
+    let mut cache = match FEED_METADATA.lock() {
+        Ok(cache) => cache,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    for (aggregator, metadata, complete) in fetched.into_iter() {
+        if complete {
+            cache.insert((source.chain_id, aggregator), metadata.clone());
+        }
+        output.insert(aggregator, metadata);
+    }
+    output
+}
+
+fn decode_description(output: &[u8]) -> Option<String> {
+    decode(&[ParamType::String], output)
+        .ok()
+        .and_then(|tokens| tokens.into_iter().next())
+        .and_then(|token| token.into_string())
+}
+
+fn process_chainlink_rounds(
+    response: ChainlinkRoundsResponse,
+    columns: &mut ChainlinkRounds,
+    schema: &Table,
+) -> R<()> {
+    let (logs, metadata) = response;
+    for log in logs.iter() {
+        if let (Some(bn), Some(tx), Some(ti), Some(li)) =
+            (log.block_number, log.transaction_hash, log.transaction_index, log.log_index)
+        {
+            let (decimals, description) = metadata.get(&log.address).cloned().unwrap_or_default();
+            let timestamp = U256::from_big_endian(&log.data);
+            let timestamp =
+                if timestamp > U256::from(u64::MAX) { None } else { Some(timestamp.as_u64()) };
+
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, bn.as_u32());
+            store!(schema, columns, transaction_index, ti.as_u32());
+            store!(schema, columns, log_index, li.as_u32());
+            store!(schema, columns, transaction_hash, tx.as_bytes().to_vec());
+            store!(schema, columns, aggregator, log.address.as_bytes().to_vec());
+            store!(schema, columns, decimals, decimals);
+            store!(schema, columns, description, description);
+            if log.topics[0] == *EVENT_CHAINLINK_ANSWER_UPDATED {
+                let raw_answer = I256::from_raw(U256::from_big_endian(log.topics[1].as_bytes()));
+                let answer = match (raw_answer.to_string().parse::<f64>(), decimals) {
+                    (Ok(answer), Some(decimals)) => Some(answer / 10f64.powi(decimals as i32)),
+                    _ => None,
+                };
+                store!(schema, columns, event, "answer_updated".to_string());
+                store!(schema, columns, round_id, U256::from_big_endian(log.topics[2].as_bytes()));
+                store!(schema, columns, answer, answer);
+                store!(schema, columns, raw_answer, Some(raw_answer.to_string()));
+                store!(schema, columns, updated_at, timestamp);
+                store!(schema, columns, started_at, None);
+                store!(schema, columns, started_by, None);
+            } else {
+                store!(schema, columns, event, "new_round".to_string());
+                store!(schema, columns, round_id, U256::from_big_endian(log.topics[1].as_bytes()));
+                store!(schema, columns, answer, None);
+                store!(schema, columns, raw_answer, None);
+                store!(schema, columns, updated_at, None);
+                store!(schema, columns, started_at, timestamp);
+                store!(schema, columns, started_by, Some(log.topics[2].as_bytes()[12..].to_vec()));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_log(topics: Vec<H256>, timestamp: u64) -> Log {
+        Log {
+            address: H160::from_low_u64_be(1),
+            topics,
+            data: H256::from_low_u64_be(timestamp).as_bytes().to_vec().into(),
+            block_number: Some(10.into()),
+            transaction_hash: Some(H256::zero()),
+            transaction_index: Some(0.into()),
+            log_index: Some(0.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_process_chainlink_rounds() {
+        // answer of -1.5 with 8 decimals in round 7
+        let answer = H256::from_slice(&{
+            let mut bytes = [0u8; 32];
+            I256::from(-150_000_000).into_raw().to_big_endian(&mut bytes);
+            bytes
+        });
+        let answer_updated = round_log(
+            vec![*EVENT_CHAINLINK_ANSWER_UPDATED, answer, H256::from_low_u64_be(7)],
+            1_700_000_000,
+        );
+        let started_by = H256::from(H160::from_low_u64_be(2));
+        let new_round = round_log(
+            vec![*EVENT_CHAINLINK_NEW_ROUND, H256::from_low_u64_be(8), started_by],
+            1_700_000_100,
+        );
+        assert!(is_round_event(&answer_updated) && is_round_event(&new_round));
+
+        let metadata = [(H160::from_low_u64_be(1), (Some(8), Some("ETH / USD".to_string())))];
+        let schema = test_schema(Datatype::ChainlinkRounds, &["all"]);
+        let mut columns = ChainlinkRounds::default();
+        let response = (vec![answer_updated, new_round], metadata.into_iter().collect());
+        process_chainlink_rounds(response, &mut columns, &schema).unwrap();
+        assert_eq!(columns.event, vec!["answer_updated", "new_round"]);
+        assert_eq!(columns.round_id, vec![U256::from(7), U256::from(8)]);
+        assert_eq!(columns.answer, vec![Some(-1.5), None]);
+        assert_eq!(columns.raw_answer, vec![Some("-150000000".to_string()), None]);
+        assert_eq!(columns.updated_at, vec![Some(1_700_000_000), None]);
+        assert_eq!(columns.started_at, vec![None, Some(1_700_000_100)]);
+        assert_eq!(
+            columns.started_by,
+            vec![None, Some(H160::from_low_u64_be(2).as_bytes().to_vec())]
+        );
+        assert_eq!(columns.description, vec![Some("ETH / USD".to_string()); 2]);
+    }
+
+    #[test]
+    fn test_decode_description() {
+        let output = ethers::abi::encode(&[ethers::abi::Token::String("ETH / USD".into())]);
+        assert_eq!(decode_description(&output), Some("ETH / USD".to_string()));
+        assert_eq!(decode_description(&[]), None);
+    }
+}
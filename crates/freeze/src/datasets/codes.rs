@@ -10,11 +10,22 @@ pub struct Codes {
     block_number: Vec<u32>,
     address: Vec<Vec<u8>>,
     code: Vec<Vec<u8>>,
+    code_size: Vec<u32>,
+    function_selectors: Vec<String>,
+    token_standards: Vec<String>,
+    compiler: Vec<Option<String>>,
+    metadata_hash: Vec<Option<String>>,
+    has_selfdestruct: Vec<bool>,
+    has_delegatecall: Vec<bool>,
     chain_id: Vec<u64>,
 }
 
 #[async_trait::async_trait]
 impl Dataset for Codes {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "address", "code", "chain_id"])
+    }
+
     fn default_sort() -> Option<Vec<&'static str>> {
         Some(vec!["block_number", "address"])
     }
@@ -62,6 +73,16 @@ fn process_nonce(columns: &mut Codes, data: BlockTxAddressOutput, schema: &Table
     columns.n_rows += 1;
     store!(schema, columns, block_number, block);
     store!(schema, columns, address, address);
+    if BYTECODE_COLUMNS.iter().any(|x| schema.has_column(x)) {
+        let analysis = analyze_bytecode(&output);
+        store!(schema, columns, code_size, analysis.code_size);
+        store!(schema, columns, function_selectors, analysis.selectors_string());
+        store!(schema, columns, token_standards, analysis.token_standards_string());
+        store!(schema, columns, compiler, analysis.compiler);
+        store!(schema, columns, metadata_hash, analysis.metadata_hash);
+        store!(schema, columns, has_selfdestruct, analysis.has_selfdestruct);
+        store!(schema, columns, has_delegatecall, analysis.has_delegatecall);
+    }
     store!(schema, columns, code, output);
     Ok(())
 }
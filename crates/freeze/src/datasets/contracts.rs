@@ -19,11 +19,34 @@ pub struct Contracts {
     code: Vec<Vec<u8>>,
     init_code_hash: Vec<Vec<u8>>,
     code_hash: Vec<Vec<u8>>,
+    code_size: Vec<u32>,
+    function_selectors: Vec<String>,
+    token_standards: Vec<String>,
+    compiler: Vec<Option<String>>,
+    metadata_hash: Vec<Option<String>>,
+    has_selfdestruct: Vec<bool>,
+    has_delegatecall: Vec<bool>,
     chain_id: Vec<u64>,
 }
 
 #[async_trait::async_trait]
 impl Dataset for Contracts {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "create_index",
+            "transaction_hash",
+            "contract_address",
+            "deployer",
+            "factory",
+            "init_code",
+            "code",
+            "init_code_hash",
+            "code_hash",
+            "chain_id",
+        ])
+    }
+
     fn default_sort() -> Option<Vec<&'static str>> {
         Some(vec!["block_number", "create_index"])
     }
@@ -68,6 +91,7 @@ pub(crate) fn process_contracts(
     let schema = schemas.get(&Datatype::Contracts).ok_or(err("schema not provided"))?;
     let mut deployer = H160([0; 20]);
     let mut create_index = 0;
+    let analyze = BYTECODE_COLUMNS.iter().any(|x| schema.has_column(x));
     for trace in traces.iter() {
         if trace.trace_address.is_empty() {
             deployer = match &trace.action {
@@ -93,6 +117,16 @@ pub(crate) fn process_contracts(
             store!(schema, columns, code, result.code.to_vec());
             store!(schema, columns, code_hash, keccak256(create.init.clone()).into());
             store!(schema, columns, init_code_hash, keccak256(result.code.clone()).into());
+            if analyze {
+                let analysis = analyze_bytecode(&result.code);
+                store!(schema, columns, code_size, analysis.code_size);
+                store!(schema, columns, function_selectors, analysis.selectors_string());
+                store!(schema, columns, token_standards, analysis.token_standards_string());
+                store!(schema, columns, compiler, analysis.compiler);
+                store!(schema, columns, metadata_hash, analysis.metadata_hash);
+                store!(schema, columns, has_selfdestruct, analysis.has_selfdestruct);
+                store!(schema, columns, has_delegatecall, analysis.has_delegatecall);
+            }
         }
     }
     Ok(())
@@ -0,0 +1,218 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// columns for dex liquidity
+#[cryo_to_df::to_df(Datatype::DexLiquidity)]
+#[derive(Default)]
+pub struct DexLiquidity {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    transaction_index: Vec<u32>,
+    log_index: Vec<u32>,
+    transaction_hash: Vec<Vec<u8>>,
+    pool: Vec<Vec<u8>>,
+    protocol: Vec<String>,
+    event: Vec<String>,
+    owner: Vec<Vec<u8>>,
+    recipient: Vec<Option<Vec<u8>>>,
+    tick_lower: Vec<Option<i32>>,
+    tick_upper: Vec<Option<i32>>,
+    liquidity: Vec<Option<U256>>,
+    token0: Vec<Option<Vec<u8>>>,
+    token1: Vec<Option<Vec<u8>>>,
+    amount0_raw: Vec<U256>,
+    amount1_raw: Vec<U256>,
+    amount0: Vec<Option<f64>>,
+    amount1: Vec<Option<f64>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for DexLiquidity {
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "log_index"])
+    }
+
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::Contract]
+    }
+
+    fn use_block_ranges() -> bool {
+        true
+    }
+}
+
+type DexLiquidityResponse = (Vec<Log>, HashMap<H160, PoolTokens>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for DexLiquidity {
+    type Response = DexLiquidityResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let events = vec![
+            Some(*EVENT_UNISWAP_V2_MINT),
+            Some(*EVENT_UNISWAP_V2_BURN),
+            Some(*EVENT_UNISWAP_V3_MINT),
+            Some(*EVENT_UNISWAP_V3_BURN),
+        ];
+        let logs = get_pool_logs(&request, &source, events).await?;
+        let logs: Vec<Log> = logs.into_iter().filter(is_liquidity_change).collect();
+        let pool_tokens = get_pool_tokens(&logs, &source).await;
+        Ok((logs, pool_tokens))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::DexLiquidity)?;
+        process_dex_liquidity(response, columns, schema)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for DexLiquidity {
+    type Response = DexLiquidityResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let logs = source.fetcher.get_transaction_logs(request.transaction_hash()?).await?;
+        let logs: Vec<Log> = logs.into_iter().filter(is_liquidity_change).collect();
+        let pool_tokens = get_pool_tokens(&logs, &source).await;
+        Ok((logs, pool_tokens))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::DexLiquidity)?;
+        process_dex_liquidity(response, columns, schema)
+    }
+}
+
+fn is_liquidity_change(log: &Log) -> bool {
+    match log.topics.first() {
+        Some(topic) if *topic == *EVENT_UNISWAP_V2_MINT => {
+            log.topics.len() == 2 && log.data.len() == 64
+        }
+        Some(topic) if *topic == *EVENT_UNISWAP_V2_BURN => {
+            log.topics.len() == 3 && log.data.len() == 64
+        }
+        Some(topic) if *topic == *EVENT_UNISWAP_V3_MINT => {
+            log.topics.len() == 4 && log.data.len() == 128
+        }
+        Some(topic) if *topic == *EVENT_UNISWAP_V3_BURN => {
+            log.topics.len() == 4 && log.data.len() == 96
+        }
+        _ => false,
+    }
+}
+
+fn process_dex_liquidity(
+    response: DexLiquidityResponse,
+    columns: &mut DexLiquidity,
+    schema: &Table,
+) -> R<()> {
+    let (logs, pool_tokens) = response;
+    for log in logs.iter() {
+        if let (Some(bn), Some(tx), Some(ti), Some(li)) =
+            (log.block_number, log.transaction_hash, log.transaction_index, log.log_index)
+        {
+            let tokens = pool_tokens.get(&log.address).cloned().unwrap_or_default();
+            let words: Vec<U256> = log.data.chunks(32).map(U256::from_big_endian).collect();
+            let topic = log.topics[0];
+            let (protocol, event) = if topic == *EVENT_UNISWAP_V2_MINT {
+                ("uniswap_v2", "mint")
+            } else if topic == *EVENT_UNISWAP_V2_BURN {
+                ("uniswap_v2", "burn")
+            } else if topic == *EVENT_UNISWAP_V3_MINT {
+                ("uniswap_v3", "mint")
+            } else {
+                ("uniswap_v3", "burn")
+            };
+            // last two words are always amount0 and amount1
+            let amount0_raw = words[words.len() - 2];
+            let amount1_raw = words[words.len() - 1];
+            let (amount0, amount1) = match event {
+                "mint" => (I256::from_raw(amount0_raw), I256::from_raw(amount1_raw)),
+                _ => (-I256::from_raw(amount0_raw), -I256::from_raw(amount1_raw)),
+            };
+
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, bn.as_u32());
+            store!(schema, columns, transaction_index, ti.as_u32());
+            store!(schema, columns, log_index, li.as_u32());
+            store!(schema, columns, transaction_hash, tx.as_bytes().to_vec());
+            store!(schema, columns, pool, log.address.as_bytes().to_vec());
+            store!(schema, columns, protocol, protocol.to_string());
+            store!(schema, columns, event, event.to_string());
+            store!(schema, columns, owner, log.topics[1].as_bytes()[12..].to_vec());
+            if protocol == "uniswap_v2" {
+                let recipient = log.topics.get(2).map(|x| x.as_bytes()[12..].to_vec());
+                store!(schema, columns, recipient, recipient);
+                store!(schema, columns, tick_lower, None);
+                store!(schema, columns, tick_upper, None);
+                store!(schema, columns, liquidity, None);
+            } else {
+                let tick_lower = I256::from_raw(U256::from_big_endian(log.topics[2].as_bytes()));
+                let tick_upper = I256::from_raw(U256::from_big_endian(log.topics[3].as_bytes()));
+                store!(schema, columns, recipient, None);
+                store!(schema, columns, tick_lower, Some(tick_lower.low_i32()));
+                store!(schema, columns, tick_upper, Some(tick_upper.low_i32()));
+                store!(schema, columns, liquidity, Some(words[words.len() - 3]));
+            }
+            store!(schema, columns, token0, tokens.token0.map(|x| x.as_bytes().to_vec()));
+            store!(schema, columns, token1, tokens.token1.map(|x| x.as_bytes().to_vec()));
+            store!(schema, columns, amount0_raw, amount0_raw);
+            store!(schema, columns, amount1_raw, amount1_raw);
+            store!(schema, columns, amount0, scale_amount(amount0, tokens.decimals0));
+            store!(schema, columns, amount1, scale_amount(amount1, tokens.decimals1));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_dex_liquidity() {
+        let word = |x: I256| {
+            let mut bytes = [0u8; 32];
+            x.into_raw().to_big_endian(&mut bytes);
+            bytes
+        };
+        let owner = H256::from(H160::from_low_u64_be(2));
+        // v3 burn of liquidity 7 in ticks [-60, 60] for amounts 300 and 5
+        let mut data = Vec::new();
+        for x in [7, 300, 5] {
+            data.extend(word(I256::from(x)));
+        }
+        let log = Log {
+            address: H160::from_low_u64_be(1),
+            topics: vec![
+                *EVENT_UNISWAP_V3_BURN,
+                owner,
+                H256(word(I256::from(-60))),
+                H256(word(I256::from(60))),
+            ],
+            data: data.into(),
+            block_number: Some(10.into()),
+            transaction_hash: Some(H256::zero()),
+            transaction_index: Some(0.into()),
+            log_index: Some(0.into()),
+            ..Default::default()
+        };
+        assert!(is_liquidity_change(&log));
+
+        let tokens = PoolTokens { decimals0: Some(2), decimals1: Some(0), ..Default::default() };
+        let pool_tokens = [(log.address, tokens)].into_iter().collect();
+        let schema = test_schema(Datatype::DexLiquidity, &["all"]);
+        let mut columns = DexLiquidity::default();
+        process_dex_liquidity((vec![log], pool_tokens), &mut columns, &schema).unwrap();
+        assert_eq!(columns.event, vec!["burn"]);
+        assert_eq!(columns.tick_lower, vec![Some(-60)]);
+        assert_eq!(columns.tick_upper, vec![Some(60)]);
+        assert_eq!(columns.liquidity, vec![Some(U256::from(7))]);
+        assert_eq!(columns.amount0_raw, vec![U256::from(300)]);
+        assert_eq!(columns.amount0, vec![Some(-3.0)]);
+        assert_eq!(columns.amount1, vec![Some(-5.0)]);
+    }
+}
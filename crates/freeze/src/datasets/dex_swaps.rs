@@ -0,0 +1,338 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+use std::{collections::HashMap, sync::Mutex};
+
+/// columns for dex swaps
+#[cryo_to_df::to_df(Datatype::DexSwaps)]
+#[derive(Default)]
+pub struct DexSwaps {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    transaction_index: Vec<u32>,
+    log_index: Vec<u32>,
+    transaction_hash: Vec<Vec<u8>>,
+    pool: Vec<Vec<u8>>,
+    protocol: Vec<String>,
+    sender: Vec<Vec<u8>>,
+    recipient: Vec<Vec<u8>>,
+    token0: Vec<Option<Vec<u8>>>,
+    token1: Vec<Option<Vec<u8>>>,
+    amount0_in: Vec<U256>,
+    amount1_in: Vec<U256>,
+    amount0_out: Vec<U256>,
+    amount1_out: Vec<U256>,
+    amount0: Vec<Option<f64>>,
+    amount1: Vec<Option<f64>>,
+    sqrt_price_x96: Vec<Option<U256>>,
+    liquidity: Vec<Option<U256>>,
+    tick: Vec<Option<i32>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for DexSwaps {
+    fn aliases() -> Vec<&'static str> {
+        vec!["swaps"]
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "log_index"])
+    }
+
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::Contract]
+    }
+
+    fn use_block_ranges() -> bool {
+        true
+    }
+}
+
+type DexSwapsResponse = (Vec<Log>, HashMap<H160, PoolTokens>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for DexSwaps {
+    type Response = DexSwapsResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let events = vec![Some(*EVENT_UNISWAP_V2_SWAP), Some(*EVENT_UNISWAP_V3_SWAP)];
+        let logs = get_pool_logs(&request, &source, events).await?;
+        let logs: Vec<Log> = logs.into_iter().filter(is_swap).collect();
+        let pool_tokens = get_pool_tokens(&logs, &source).await;
+        Ok((logs, pool_tokens))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::DexSwaps)?;
+        process_dex_swaps(response, columns, schema)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for DexSwaps {
+    type Response = DexSwapsResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let logs = source.fetcher.get_transaction_logs(request.transaction_hash()?).await?;
+        let logs: Vec<Log> = logs.into_iter().filter(is_swap).collect();
+        let pool_tokens = get_pool_tokens(&logs, &source).await;
+        Ok((logs, pool_tokens))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::DexSwaps)?;
+        process_dex_swaps(response, columns, schema)
+    }
+}
+
+fn is_swap(log: &Log) -> bool {
+    match log.topics.first() {
+        Some(topic) if *topic == *EVENT_UNISWAP_V2_SWAP => {
+            log.topics.len() == 3 && log.data.len() == 128
+        }
+        Some(topic) if *topic == *EVENT_UNISWAP_V3_SWAP => {
+            log.topics.len() == 3 && log.data.len() == 160
+        }
+        _ => false,
+    }
+}
+
+fn process_dex_swaps(response: DexSwapsResponse, columns: &mut DexSwaps, schema: &Table) -> R<()> {
+    let (logs, pool_tokens) = response;
+    for log in logs.iter() {
+        if let (Some(bn), Some(tx), Some(ti), Some(li)) =
+            (log.block_number, log.transaction_hash, log.transaction_index, log.log_index)
+        {
+            let tokens = pool_tokens.get(&log.address).cloned().unwrap_or_default();
+            let words: Vec<U256> = log.data.chunks(32).map(U256::from_big_endian).collect();
+            // amounts are signed from the perspective of the pool, positive amounts flow in
+            let (protocol, amount0, amount1) = if log.topics[0] == *EVENT_UNISWAP_V2_SWAP {
+                let amount0 = I256::from_raw(words[0]) - I256::from_raw(words[2]);
+                let amount1 = I256::from_raw(words[1]) - I256::from_raw(words[3]);
+                ("uniswap_v2", amount0, amount1)
+            } else {
+                ("uniswap_v3", I256::from_raw(words[0]), I256::from_raw(words[1]))
+            };
+
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, bn.as_u32());
+            store!(schema, columns, transaction_index, ti.as_u32());
+            store!(schema, columns, log_index, li.as_u32());
+            store!(schema, columns, transaction_hash, tx.as_bytes().to_vec());
+            store!(schema, columns, pool, log.address.as_bytes().to_vec());
+            store!(schema, columns, protocol, protocol.to_string());
+            store!(schema, columns, sender, log.topics[1].as_bytes()[12..].to_vec());
+            store!(schema, columns, recipient, log.topics[2].as_bytes()[12..].to_vec());
+            store!(schema, columns, token0, tokens.token0.map(|x| x.as_bytes().to_vec()));
+            store!(schema, columns, token1, tokens.token1.map(|x| x.as_bytes().to_vec()));
+            if protocol == "uniswap_v2" {
+                store!(schema, columns, amount0_in, words[0]);
+                store!(schema, columns, amount1_in, words[1]);
+                store!(schema, columns, amount0_out, words[2]);
+                store!(schema, columns, amount1_out, words[3]);
+                store!(schema, columns, sqrt_price_x96, None);
+                store!(schema, columns, liquidity, None);
+                store!(schema, columns, tick, None);
+            } else {
+                store!(schema, columns, amount0_in, positive_part(amount0));
+                store!(schema, columns, amount1_in, positive_part(amount1));
+                store!(schema, columns, amount0_out, positive_part(-amount0));
+                store!(schema, columns, amount1_out, positive_part(-amount1));
+                store!(schema, columns, sqrt_price_x96, Some(words[2]));
+                store!(schema, columns, liquidity, Some(words[3]));
+                store!(schema, columns, tick, Some(I256::from_raw(words[4]).low_i32()));
+            }
+            store!(schema, columns, amount0, scale_amount(amount0, tokens.decimals0));
+            store!(schema, columns, amount1, scale_amount(amount1, tokens.decimals1));
+        }
+    }
+    Ok(())
+}
+
+fn positive_part(value: I256) -> U256 {
+    if value.is_negative() {
+        U256::zero()
+    } else {
+        value.into_raw()
+    }
+}
+
+/// tokens of a uniswap pool
+#[derive(Clone, Default)]
+pub struct PoolTokens {
+    /// address of token0
+    pub token0: Option<H160>,
+    /// address of token1
+    pub token1: Option<H160>,
+    /// decimals of token0
+    pub decimals0: Option<u32>,
+    /// decimals of token1
+    pub decimals1: Option<u32>,
+}
+
+lazy_static::lazy_static! {
+    /// pool tokens fully resolved, keyed by chain id and pool address
+    static ref POOL_TOKENS: Mutex<HashMap<(u64, H160), PoolTokens>> = Mutex::new(HashMap::new());
+}
+
+/// fetch logs of given events from pools of request, optionally filtered by --contract
+pub(crate) async fn get_pool_logs(
+    request: &Params,
+    source: &Source,
+    events: Vec<Option<H256>>,
+) -> R<Vec<Log>> {
+    let topics = [Some(ValueOrArray::Array(events)), None, None, None];
+    let address = request.ethers_contract().ok().map(ValueOrArray::Value);
+    let filter = Filter { topics, address, ..request.ethers_log_filter()? };
+    source.fetcher.get_logs(&filter).await
+}
+
+/// resolve token0, token1 and their decimals for each pool of logs
+///
+/// pools are queried at the last block of their logs and, once every call succeeds, cached for
+/// the process. contracts that are not pools give null tokens
+pub(crate) async fn get_pool_tokens(logs: &[Log], source: &Source) -> HashMap<H160, PoolTokens> {
+    let mut pools: HashMap<H160, BlockNumber> = HashMap::new();
+    for log in logs.iter() {
+        let block_number = log.block_number.map(BlockNumber::Number).unwrap_or(BlockNumber::Latest);
+        let pool_block = pools.entry(log.address).or_insert(block_number);
+        if block_number.as_number() > pool_block.as_number() {
+            *pool_block = block_number;
+        }
+    }
+
+    let mut output = HashMap::new();
+    let mut futures = Vec::new();
+    {
+        let cache = match POOL_TOKENS.lock() {
+            Ok(cache) => cache,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        for (pool, block_number) in pools.into_iter() {
+            match cache.get(&(source.chain_id, pool)) {
+                Some(tokens) => {
+                    output.insert(pool, tokens.clone());
+                }
+                None => {
+                    let fetcher = source.fetcher.clone();
+                    futures.push(async move {
+                        (pool, fetch_pool_tokens(pool, block_number, fetcher).await)
+                    });
+                }
+            }
+        }
+    }
+
+    let fetched = futures::future::join_all(futures).await;
+    let mut cache = match POOL_TOKENS.lock() {
+        Ok(cache) => cache,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    for (pool, (tokens, complete)) in fetched.into_iter() {
+        // failed calls may be transient, so incomplete tokens are retried in later chunks
+        if complete {
+            cache.insert((source.chain_id, pool), tokens.clone());
+        }
+        output.insert(pool, tokens);
+    }
+    output
+}
+
+/// fetch tokens of pool, and whether every call succeeded
+async fn fetch_pool_tokens(
+    pool: H160,
+    block_number: BlockNumber,
+    fetcher: Arc<Fetcher<RetryClient<Http>>>,
+) -> (PoolTokens, bool) {
+    let call = |contract, call_data| fetcher.call2(contract, call_data, block_number);
+    let (token0, token1) = futures::join!(
+        call(pool, FUNCTION_UNISWAP_TOKEN0.clone()),
+        call(pool, FUNCTION_UNISWAP_TOKEN1.clone()),
+    );
+    let mut complete = token0.is_ok() && token1.is_ok();
+    let token0 = token0.ok().and_then(|x| bytes_to_address(&x));
+    let token1 = token1.ok().and_then(|x| bytes_to_address(&x));
+    let mut decimals = Vec::new();
+    for token in [token0, token1] {
+        match token {
+            Some(token) => match call(token, FUNCTION_ERC20_DECIMALS.clone()).await {
+                Ok(output) => decimals.push(bytes_to_u32(output).ok()),
+                Err(_) => {
+                    complete = false;
+                    decimals.push(None)
+                }
+            },
+            None => decimals.push(None),
+        }
+    }
+    let tokens = PoolTokens { token0, token1, decimals0: decimals[0], decimals1: decimals[1] };
+    (tokens, complete)
+}
+
+/// convert a raw signed token amount to a float in units of the token
+pub(crate) fn scale_amount(amount: I256, decimals: Option<u32>) -> Option<f64> {
+    let amount = amount.to_string().parse::<f64>().ok()?;
+    Some(amount / 10f64.powi(decimals? as i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// log of a pool with data from signed words
+    fn pool_log(topics: Vec<H256>, words: &[I256]) -> Log {
+        let mut data = Vec::new();
+        for word in words.iter() {
+            let mut bytes = [0u8; 32];
+            word.into_raw().to_big_endian(&mut bytes);
+            data.extend(bytes);
+        }
+        Log {
+            address: H160::from_low_u64_be(1),
+            topics,
+            data: data.into(),
+            block_number: Some(10.into()),
+            transaction_hash: Some(H256::zero()),
+            transaction_index: Some(0.into()),
+            log_index: Some(0.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_scale_amount() {
+        assert_eq!(scale_amount(I256::from(-1_500_000), Some(6)), Some(-1.5));
+        assert_eq!(scale_amount(I256::exp10(18), Some(18)), Some(1.0));
+        assert_eq!(scale_amount(I256::from(5), None), None);
+    }
+
+    #[test]
+    fn test_process_dex_swaps() {
+        let address = |x: u64| H256::from(H160::from_low_u64_be(x));
+        let v2 = pool_log(
+            vec![*EVENT_UNISWAP_V2_SWAP, address(2), address(3)],
+            &[I256::from(100), I256::zero(), I256::zero(), I256::from(250)],
+        );
+        let v3 = pool_log(
+            vec![*EVENT_UNISWAP_V3_SWAP, address(2), address(3)],
+            &[I256::from(-40), I256::from(90), I256::from(7), I256::from(8), I256::from(-5)],
+        );
+        assert!(is_swap(&v2) && is_swap(&v3));
+
+        let tokens = PoolTokens { decimals0: Some(1), decimals1: None, ..Default::default() };
+        let pool_tokens = [(H160::from_low_u64_be(1), tokens)].into_iter().collect();
+        let schema = test_schema(Datatype::DexSwaps, &["all"]);
+        let mut columns = DexSwaps::default();
+        process_dex_swaps((vec![v2, v3], pool_tokens), &mut columns, &schema).unwrap();
+        assert_eq!(columns.protocol, vec!["uniswap_v2", "uniswap_v3"]);
+        assert_eq!(columns.amount0, vec![Some(10.0), Some(-4.0)]);
+        assert_eq!(columns.amount1, vec![None, None]);
+        assert_eq!(columns.amount0_in, vec![U256::from(100), U256::zero()]);
+        assert_eq!(columns.amount0_out, vec![U256::zero(), U256::from(40)]);
+        assert_eq!(columns.amount1_in, vec![U256::zero(), U256::from(90)]);
+        assert_eq!(columns.amount1_out, vec![U256::from(250), U256::zero()]);
+        assert_eq!(columns.tick, vec![None, Some(-5)]);
+        assert_eq!(columns.recipient[0], H160::from_low_u64_be(3).as_bytes().to_vec());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::*;
+use ethers::{
+    abi::{decode, ParamType},
+    prelude::*,
+    providers::ens::{namehash, reverse_address, ENS_ADDRESS},
+};
+use polars::prelude::*;
+
+/// columns for ens names
+#[cryo_to_df::to_df(Datatype::EnsNames)]
+#[derive(Default)]
+pub struct EnsNames {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    address: Vec<Vec<u8>>,
+    name: Vec<Option<String>>,
+    resolver: Vec<Option<Vec<u8>>>,
+    forward_resolver: Vec<Option<Vec<u8>>>,
+    forward_address: Vec<Option<Vec<u8>>>,
+    verified: Vec<Option<bool>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for EnsNames {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "address", "name", "resolver", "verified", "chain_id"])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "address"])
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("latest".to_string())
+    }
+
+    fn required_parameters() -> Vec<Dim> {
+        vec![Dim::Address]
+    }
+}
+
+/// reverse and forward resolution of an address
+#[derive(Default)]
+pub struct EnsResolution {
+    block_number: u32,
+    address: Vec<u8>,
+    name: Option<String>,
+    resolver: Option<H160>,
+    forward_resolver: Option<H160>,
+    forward_address: Option<H160>,
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for EnsNames {
+    type Response = EnsResolution;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let address = request.ethers_address()?;
+        let block_number = request.block_number()?;
+        let block: BlockNumber = block_number.into();
+        let mut resolution = EnsResolution {
+            block_number: block_number as u32,
+            address: request.address()?,
+            ..Default::default()
+        };
+
+        // reverse resolution, from the resolver of <address>.addr.reverse
+        let reverse_node = namehash(&reverse_address(address));
+        resolution.resolver =
+            call_node(&source, ENS_ADDRESS, &FUNCTION_ENS_RESOLVER, reverse_node, block)
+                .await?
+                .and_then(|x| bytes_to_address(&x));
+        let resolver = match resolution.resolver {
+            Some(resolver) => resolver,
+            None => return Ok(resolution),
+        };
+        resolution.name = call_node(&source, resolver, &FUNCTION_ENS_NAME, reverse_node, block)
+            .await?
+            .and_then(|x| decode_ens_name(&x));
+        let name = match &resolution.name {
+            Some(name) => name,
+            None => return Ok(resolution),
+        };
+
+        // forward resolution, to verify that the name resolves back to the address
+        let node = namehash(name);
+        resolution.forward_resolver =
+            call_node(&source, ENS_ADDRESS, &FUNCTION_ENS_RESOLVER, node, block)
+                .await?
+                .and_then(|x| bytes_to_address(&x));
+        if let Some(forward_resolver) = resolution.forward_resolver {
+            resolution.forward_address =
+                call_node(&source, forward_resolver, &FUNCTION_ENS_ADDR, node, block)
+                    .await?
+                    .and_then(|x| bytes_to_address(&x));
+        }
+        Ok(resolution)
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::EnsNames)?;
+        let to_bytes = |x: Option<H160>| x.map(|x| x.as_bytes().to_vec());
+        let verified = response.name.as_ref().map(|_| {
+            response.forward_address.map(|x| x.as_bytes().to_vec()) ==
+                Some(response.address.clone())
+        });
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, response.block_number);
+        store!(schema, columns, address, response.address);
+        store!(schema, columns, name, response.name);
+        store!(schema, columns, resolver, to_bytes(response.resolver));
+        store!(schema, columns, forward_resolver, to_bytes(response.forward_resolver));
+        store!(schema, columns, forward_address, to_bytes(response.forward_address));
+        store!(schema, columns, verified, verified);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for EnsNames {
+    type Response = ();
+}
+
+/// call a function taking an ens node, reverted calls give None
+async fn call_node(
+    source: &Source,
+    address: H160,
+    selector: &[u8],
+    node: H256,
+    block_number: BlockNumber,
+) -> R<Option<Bytes>> {
+    let mut call_data = selector.to_vec();
+    call_data.extend(node.as_bytes());
+    source.fetcher.call_opt(address, call_data, block_number).await
+}
+
+/// decode output of name(bytes32), empty names give None
+fn decode_ens_name(output: &[u8]) -> Option<String> {
+    let name = decode(&[ParamType::String], output).ok()?.into_iter().next()?.into_string()?;
+    let name = remove_control_characters(&name);
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_ens_name() {
+        let output = ethers::abi::encode(&[ethers::abi::Token::String("vitalik.eth".into())]);
+        assert_eq!(decode_ens_name(&output), Some("vitalik.eth".to_string()));
+
+        let output = ethers::abi::encode(&[ethers::abi::Token::String("".into())]);
+        assert_eq!(decode_ens_name(&output), None);
+        assert_eq!(decode_ens_name(&[]), None);
+    }
+}
@@ -0,0 +1,200 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// columns for erc20 holders
+#[cryo_to_df::to_df(Datatype::Erc20Holders)]
+#[derive(Default)]
+pub struct Erc20Holders {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    erc20: Vec<Vec<u8>>,
+    address: Vec<Vec<u8>>,
+    balance: Vec<U256>,
+    verified: Vec<Option<bool>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for Erc20Holders {
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["erc20", "block_number", "address"])
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("latest".to_string())
+    }
+
+    fn required_parameters() -> Vec<Dim> {
+        vec![Dim::Contract]
+    }
+}
+
+/// block number, erc20 contract, holder balances, and spot check result of each holder
+type Erc20HoldersResponse = (u32, Vec<u8>, Vec<(H160, U256, Option<bool>)>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for Erc20Holders {
+    type Response = Erc20HoldersResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let block_number = request.block_number()?;
+        let transfer_files = query
+            .transfer_files
+            .as_ref()
+            .ok_or(err("must specify --transfers for erc20_holders"))?;
+        let transfers = transfer_files.read(
+            Datatype::Erc20Transfers,
+            &request.contract()?,
+            block_number as u32,
+        )?;
+        let balances = replay_balances(transfers)?;
+
+        let verified = match query.spot_check {
+            Some(n_samples) => {
+                let sample = sample_holders(&balances, block_number, n_samples as usize);
+                spot_check(&request, &source, sample).await?
+            }
+            None => HashMap::new(),
+        };
+        let holders = balances
+            .into_iter()
+            .map(|(address, balance)| (address, balance, verified.get(&address).cloned()))
+            .collect();
+        Ok((block_number as u32, request.contract()?, holders))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::Erc20Holders)?;
+        let (block_number, erc20, holders) = response;
+        for (address, balance, verified) in holders.into_iter() {
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, block_number);
+            store!(schema, columns, erc20, erc20.clone());
+            store!(schema, columns, address, address.as_bytes().to_vec());
+            store!(schema, columns, balance, balance);
+            store!(schema, columns, verified, verified);
+        }
+        Ok(())
+    }
+}
+
+impl CollectByTransaction for Erc20Holders {
+    type Response = ();
+}
+
+/// derive nonzero balances by replaying transfers from erc20_transfers files
+fn replay_balances(transfers: Vec<TransferRow>) -> R<HashMap<H160, U256>> {
+    let mut balances: HashMap<H160, U256> = HashMap::new();
+    for transfer in transfers.into_iter() {
+        if transfer.from_address.len() != 20 || transfer.to_address.len() != 20 {
+            return Err(err("invalid address in erc20_transfers file"))
+        }
+        let value = transfer.value;
+        let from_address = H160::from_slice(&transfer.from_address);
+        let to_address = H160::from_slice(&transfer.to_address);
+        // the zero address is the source of mints and the sink of burns
+        if !from_address.is_zero() {
+            let balance = balances.entry(from_address).or_default();
+            *balance = balance.checked_sub(value).ok_or_else(|| {
+                err("negative balance in replay, transfer files must cover token history")
+            })?;
+        }
+        if !to_address.is_zero() {
+            let balance = balances.entry(to_address).or_default();
+            *balance =
+                balance.checked_add(value).ok_or_else(|| err("balance overflow in replay"))?;
+        }
+    }
+    balances.retain(|_, balance| !balance.is_zero());
+    Ok(balances)
+}
+
+/// choose a pseudorandom sample of holders, stable for a given block
+fn sample_holders(
+    balances: &HashMap<H160, U256>,
+    block_number: u64,
+    n_samples: usize,
+) -> Vec<(H160, U256)> {
+    let mut keyed: Vec<([u8; 32], H160, U256)> = balances
+        .iter()
+        .map(|(address, balance)| {
+            let mut seed = address.as_bytes().to_vec();
+            seed.extend(block_number.to_be_bytes());
+            (ethers_core::utils::keccak256(seed), *address, *balance)
+        })
+        .collect();
+    keyed.sort();
+    keyed.into_iter().take(n_samples).map(|(_, address, balance)| (address, balance)).collect()
+}
+
+/// compare replayed balances of holders against balanceOf() at the same block
+async fn spot_check(
+    request: &Params,
+    source: &Source,
+    sample: Vec<(H160, U256)>,
+) -> R<HashMap<H160, bool>> {
+    let block_number = request.block_number()?;
+    let contract = request.ethers_contract()?;
+    let futures = sample.into_iter().map(|(address, balance)| async move {
+        let mut call_data = FUNCTION_ERC20_BALANCE_OF.clone();
+        call_data.extend(vec![0; 12]);
+        call_data.extend(address.as_bytes());
+        let output = source.fetcher.call2(contract, call_data, block_number.into()).await?;
+        let verified = bytes_to_u256(&output) == Some(balance);
+        Ok::<_, CollectError>((address, verified))
+    });
+    futures::future::join_all(futures).await.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(from: u64, to: u64, value: u64) -> TransferRow {
+        TransferRow {
+            block_number: 0,
+            transaction_index: 0,
+            log_index: 0,
+            from_address: H160::from_low_u64_be(from).as_bytes().to_vec(),
+            to_address: H160::from_low_u64_be(to).as_bytes().to_vec(),
+            value: U256::from(value),
+        }
+    }
+
+    #[test]
+    fn test_replay_balances() {
+        let address = H160::from_low_u64_be;
+        // mint 100 to 1, 1 sends 30 to 2, 2 sends all back, 1 burns 20
+        let transfers =
+            vec![transfer(0, 1, 100), transfer(1, 2, 30), transfer(2, 1, 30), transfer(1, 0, 20)];
+        let balances = replay_balances(transfers).unwrap();
+        assert_eq!(balances, [(address(1), U256::from(80))].into_iter().collect());
+
+        // a sender without replayed history would have a negative balance
+        assert!(replay_balances(vec![transfer(1, 2, 5)]).is_err());
+
+        // transfers of at least 2^255 are still positive amounts
+        let mut mint = transfer(0, 1, 0);
+        mint.value = U256::MAX;
+        let mut send = transfer(1, 2, 0);
+        send.value = U256::MAX - 1;
+        let balances = replay_balances(vec![mint.clone(), send]).unwrap();
+        let expected = [(address(1), U256::one()), (address(2), U256::MAX - 1)];
+        assert_eq!(balances, expected.into_iter().collect());
+        assert!(replay_balances(vec![mint.clone(), mint]).is_err());
+    }
+
+    #[test]
+    fn test_sample_holders() {
+        let balances: HashMap<H160, U256> =
+            (1..=10).map(|i| (H160::from_low_u64_be(i), U256::from(i))).collect();
+        let sample = sample_holders(&balances, 100, 3);
+        assert_eq!(sample.len(), 3);
+        assert!(sample.iter().all(|(address, balance)| balances.get(address) == Some(balance)));
+        assert_eq!(sample, sample_holders(&balances, 100, 3));
+        assert_ne!(sample, sample_holders(&balances, 101, 3));
+        assert_eq!(sample_holders(&balances, 100, 20).len(), 10);
+    }
+}
@@ -1,4 +1,8 @@
 use crate::*;
+use ethers::{
+    abi::{decode, ParamType},
+    prelude::*,
+};
 use polars::prelude::*;
 
 /// columns for transactions
@@ -11,11 +15,18 @@ pub struct Erc20Metadata {
     name: Vec<Option<String>>,
     symbol: Vec<Option<String>>,
     decimals: Vec<Option<u32>>,
+    status: Vec<String>,
+    supports_erc165: Vec<bool>,
+    token_standard: Vec<Option<String>>,
     chain_id: Vec<u64>,
 }
 
 #[async_trait::async_trait]
 impl Dataset for Erc20Metadata {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "erc20", "name", "symbol", "decimals", "status", "chain_id"])
+    }
+
     fn default_sort() -> Option<Vec<&'static str>> {
         Some(vec!["symbol", "block_number"])
     }
@@ -38,41 +49,164 @@ pub(crate) fn remove_control_characters(s: &str) -> String {
     re.replace_all(s, "").to_string()
 }
 
+/// a string value returned by a metadata getter
+pub enum MetadataValue {
+    /// returned as an abi encoded string
+    String(String),
+    /// returned as a zero padded bytes32, as done by tokens like MKR
+    Bytes32(String),
+}
+
+impl MetadataValue {
+    /// decoded string, regardless of return type
+    pub fn into_string(self) -> String {
+        match self {
+            MetadataValue::String(s) | MetadataValue::Bytes32(s) => s,
+        }
+    }
+}
+
+/// decode output of a string getter, accepting both string and bytes32 returns
+pub(crate) fn decode_metadata_string(output: &[u8]) -> Option<MetadataValue> {
+    if output.len() == 32 {
+        let end = output.iter().rposition(|b| *b != 0)? + 1;
+        let s = String::from_utf8(output[..end].to_vec()).ok()?;
+        return Some(MetadataValue::Bytes32(remove_control_characters(&s)))
+    }
+    let bytes = decode(&[ParamType::Bytes], output).ok()?.into_iter().next()?.into_bytes()?;
+    let s = String::from_utf8(bytes).ok()?;
+    Some(MetadataValue::String(remove_control_characters(&s)))
+}
+
+/// call a string getter, failed calls and undecodable outputs give None
+pub(crate) async fn call_metadata_string(
+    source: &Source,
+    address: H160,
+    call_data: Vec<u8>,
+    block_number: BlockNumber,
+) -> Option<MetadataValue> {
+    let output = source.fetcher.call2(address, call_data, block_number).await.ok()?;
+    decode_metadata_string(&output)
+}
+
+/// summarize metadata calls, where each field is None if missing or Some(is_bytes32)
+///
+/// gives `ok`, `bytes32` if any field used the bytes32 variant, `partial` if some fields are
+/// missing, or `non_compliant` if all fields are missing
+pub(crate) fn metadata_status(fields: &[Option<bool>]) -> String {
+    let status = if fields.iter().all(|x| x.is_none()) {
+        "non_compliant"
+    } else if fields.iter().any(|x| x.is_none()) {
+        "partial"
+    } else if fields.contains(&Some(true)) {
+        "bytes32"
+    } else {
+        "ok"
+    };
+    status.to_string()
+}
+
+pub(crate) fn value_status(value: &Option<MetadataValue>) -> Option<bool> {
+    value.as_ref().map(|x| matches!(x, MetadataValue::Bytes32(_)))
+}
+
+/// probe erc165 supportsInterface() for the interfaces of token standards
+///
+/// returns whether the contract implements erc165, and the token standard it reports
+pub(crate) async fn probe_token_standard(
+    source: &Source,
+    address: H160,
+    block_number: BlockNumber,
+) -> (bool, Option<String>) {
+    let supports = |interface_id: [u8; 4]| async move {
+        let mut call_data = FUNCTION_ERC165_SUPPORTS_INTERFACE.clone();
+        call_data.extend(interface_id);
+        call_data.extend([0u8; 28]);
+        match source.fetcher.call2(address, call_data, block_number).await {
+            Ok(output) => output.len() == 32 && U256::from_big_endian(&output) == U256::one(),
+            Err(_) => false,
+        }
+    };
+    let (erc165, invalid) = futures::join!(supports(ERC165_INTERFACE_ID), supports([0xff; 4]));
+    if !erc165 || invalid {
+        return (false, None)
+    }
+    let (erc721, erc1155, erc20) = futures::join!(
+        supports(ERC721_INTERFACE_ID),
+        supports(ERC1155_INTERFACE_ID),
+        supports(ERC20_INTERFACE_ID)
+    );
+    let standard = if erc721 {
+        Some("erc721")
+    } else if erc1155 {
+        Some("erc1155")
+    } else if erc20 {
+        Some("erc20")
+    } else {
+        None
+    };
+    (true, standard.map(|x| x.to_string()))
+}
+
+const ERC165_INTERFACE_ID: [u8; 4] = [0x01, 0xff, 0xc9, 0xa7];
+const ERC20_INTERFACE_ID: [u8; 4] = [0x36, 0x37, 0x2b, 0x07];
+const ERC721_INTERFACE_ID: [u8; 4] = [0x80, 0xac, 0x58, 0xcd];
+const ERC1155_INTERFACE_ID: [u8; 4] = [0xd9, 0xb6, 0x7a, 0x26];
+
+type Erc20MetadataResponse = (
+    u32,
+    Vec<u8>,
+    Option<MetadataValue>,
+    Option<MetadataValue>,
+    Option<u32>,
+    Option<(bool, Option<String>)>,
+);
+
 #[async_trait::async_trait]
 impl CollectByBlock for Erc20Metadata {
-    type Response = (u32, Vec<u8>, Option<String>, Option<String>, Option<u32>);
+    type Response = Erc20MetadataResponse;
 
-    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::Erc20Metadata)?;
         let block_number = request.ethers_block_number()?;
         let address = request.ethers_address()?;
 
-        // name
-        let call_data = FUNCTION_ERC20_NAME.clone();
-        let output = source.fetcher.call2(address, call_data, block_number).await?;
-        let name = String::from_utf8(output.to_vec()).ok().map(|s| remove_control_characters(&s));
+        let (name, symbol, decimals) = futures::join!(
+            call_metadata_string(&source, address, FUNCTION_ERC20_NAME.clone(), block_number),
+            call_metadata_string(&source, address, FUNCTION_ERC20_SYMBOL.clone(), block_number),
+            source.fetcher.call2(address, FUNCTION_ERC20_DECIMALS.clone(), block_number),
+        );
+        let decimals = decimals.ok().and_then(|x| bytes_to_u32(x).ok());
 
-        // symbol
-        let call_data = FUNCTION_ERC20_SYMBOL.clone();
-        let output = source.fetcher.call2(address, call_data, block_number).await?;
-        let symbol = String::from_utf8(output.to_vec()).ok().map(|s| remove_control_characters(&s));
+        let probe = if schema.has_column("supports_erc165") | schema.has_column("token_standard") {
+            Some(probe_token_standard(&source, address, block_number).await)
+        } else {
+            None
+        };
 
-        // decimals
-        let call_data = FUNCTION_ERC20_DECIMALS.clone();
-        let output = source.fetcher.call2(address, call_data, block_number).await?;
-        let decimals = bytes_to_u32(output).ok();
-
-        Ok((request.block_number()? as u32, request.address()?, name, symbol, decimals))
+        Ok((request.block_number()? as u32, request.address()?, name, symbol, decimals, probe))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let schema = query.schemas.get_schema(&Datatype::Erc20Metadata)?;
-        let (block, address, name, symbol, decimals) = response;
+        let (block, address, name, symbol, decimals, probe) = response;
+        let status =
+            metadata_status(&[value_status(&name), value_status(&symbol), decimals.map(|_| false)]);
+        let (supports_erc165, token_standard) = probe.unwrap_or_default();
+        // tokens without erc165 are taken to be erc20 if they report decimals
+        let token_standard = match (token_standard, decimals) {
+            (None, Some(_)) => Some("erc20".to_string()),
+            (token_standard, _) => token_standard,
+        };
         columns.n_rows += 1;
         store!(schema, columns, block_number, block);
         store!(schema, columns, erc20, address);
-        store!(schema, columns, name, name);
-        store!(schema, columns, symbol, symbol);
+        store!(schema, columns, name, name.map(|x| x.into_string()));
+        store!(schema, columns, symbol, symbol.map(|x| x.into_string()));
         store!(schema, columns, decimals, decimals);
+        store!(schema, columns, status, status);
+        store!(schema, columns, supports_erc165, supports_erc165);
+        store!(schema, columns, token_standard, token_standard);
         Ok(())
     }
 }
@@ -80,3 +214,26 @@ impl CollectByBlock for Erc20Metadata {
 impl CollectByTransaction for Erc20Metadata {
     type Response = ();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_metadata_string() {
+        // MKR returns its symbol as bytes32
+        let mut output = b"MKR".to_vec();
+        output.extend([0u8; 29]);
+        assert!(
+            matches!(decode_metadata_string(&output), Some(MetadataValue::Bytes32(s)) if s == "MKR")
+        );
+
+        let output = ethers::abi::encode(&[ethers::abi::Token::String("Wrapped Ether".into())]);
+        assert!(
+            matches!(decode_metadata_string(&output), Some(MetadataValue::String(s)) if s == "WrappedEther")
+        );
+
+        assert!(decode_metadata_string(&[0u8; 32]).is_none());
+        assert!(decode_metadata_string(&[]).is_none());
+    }
+}
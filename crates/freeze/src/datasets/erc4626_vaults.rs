@@ -0,0 +1,160 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+
+/// columns for erc4626 vaults
+#[cryo_to_df::to_df(Datatype::Erc4626Vaults)]
+#[derive(Default)]
+pub struct Erc4626Vaults {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    vault: Vec<Vec<u8>>,
+    asset: Vec<Option<Vec<u8>>>,
+    decimals: Vec<Option<u32>>,
+    asset_decimals: Vec<Option<u32>>,
+    total_assets: Vec<Option<U256>>,
+    total_supply: Vec<Option<U256>>,
+    assets_per_share: Vec<Option<U256>>,
+    share_price: Vec<Option<f64>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for Erc4626Vaults {
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["vault", "block_number"])
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("latest".to_string())
+    }
+
+    fn required_parameters() -> Vec<Dim> {
+        vec![Dim::Contract]
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Address, Dim::Contract)].into_iter().collect())
+    }
+}
+
+/// state of a vault at a block, fields are None when the vault does not implement them
+#[derive(Default)]
+pub struct VaultState {
+    block_number: u32,
+    vault: Vec<u8>,
+    asset: Option<H160>,
+    decimals: Option<u32>,
+    asset_decimals: Option<u32>,
+    total_assets: Option<U256>,
+    total_supply: Option<U256>,
+    assets_per_share: Option<U256>,
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for Erc4626Vaults {
+    type Response = VaultState;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let block_number = request.block_number()?;
+        let vault = request.ethers_contract()?;
+        let (asset, decimals, total_assets, total_supply) = futures::join!(
+            source.fetcher.call_opt(vault, FUNCTION_ERC4626_ASSET.clone(), block_number.into()),
+            source.fetcher.call_opt(vault, FUNCTION_ERC20_DECIMALS.clone(), block_number.into()),
+            source.fetcher.call_opt(
+                vault,
+                FUNCTION_ERC4626_TOTAL_ASSETS.clone(),
+                block_number.into()
+            ),
+            source.fetcher.call_opt(
+                vault,
+                FUNCTION_ERC20_TOTAL_SUPPLY.clone(),
+                block_number.into()
+            ),
+        );
+        let asset = asset?.and_then(|x| bytes_to_address(&x));
+        let decimals = decimals?.and_then(decode_decimals);
+
+        // value of one whole share, in base units of the asset
+        let (asset_decimals, assets_per_share) = match (asset, decimals) {
+            (Some(asset), Some(decimals)) => {
+                let mut call_data = FUNCTION_ERC4626_CONVERT_TO_ASSETS.clone();
+                call_data.extend(ethers::abi::encode(&[ethers::abi::Token::Uint(U256::exp10(
+                    decimals as usize,
+                ))]));
+                let (asset_decimals, assets_per_share) = futures::join!(
+                    source.fetcher.call_opt(
+                        asset,
+                        FUNCTION_ERC20_DECIMALS.clone(),
+                        block_number.into()
+                    ),
+                    source.fetcher.call_opt(vault, call_data, block_number.into()),
+                );
+                (
+                    asset_decimals?.and_then(decode_decimals),
+                    assets_per_share?.and_then(|x| bytes_to_u256(&x)),
+                )
+            }
+            _ => (None, None),
+        };
+
+        Ok(VaultState {
+            block_number: block_number as u32,
+            vault: request.contract()?,
+            asset,
+            decimals,
+            asset_decimals,
+            total_assets: total_assets?.and_then(|x| bytes_to_u256(&x)),
+            total_supply: total_supply?.and_then(|x| bytes_to_u256(&x)),
+            assets_per_share,
+        })
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::Erc4626Vaults)?;
+        let share_price = match (response.assets_per_share, response.asset_decimals) {
+            (Some(assets_per_share), Some(asset_decimals)) => assets_per_share
+                .to_string()
+                .parse::<f64>()
+                .ok()
+                .map(|assets_per_share| assets_per_share / 10f64.powi(asset_decimals as i32)),
+            _ => None,
+        };
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, response.block_number);
+        store!(schema, columns, vault, response.vault);
+        store!(schema, columns, asset, response.asset.map(|x| x.as_bytes().to_vec()));
+        store!(schema, columns, decimals, response.decimals);
+        store!(schema, columns, asset_decimals, response.asset_decimals);
+        store!(schema, columns, total_assets, response.total_assets);
+        store!(schema, columns, total_supply, response.total_supply);
+        store!(schema, columns, assets_per_share, response.assets_per_share);
+        store!(schema, columns, share_price, share_price);
+        Ok(())
+    }
+}
+
+impl CollectByTransaction for Erc4626Vaults {
+    type Response = ();
+}
+
+/// decode decimals() output, values too large for a U256 power of ten are treated as unknown
+fn decode_decimals(output: Bytes) -> Option<u32> {
+    bytes_to_u32(output).ok().filter(|decimals| *decimals <= 77)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_decimals() {
+        let word = |value: u64| {
+            Bytes::from(ethers::abi::encode(&[ethers::abi::Token::Uint(value.into())]))
+        };
+        assert_eq!(decode_decimals(word(18)), Some(18));
+        assert_eq!(decode_decimals(word(77)), Some(77));
+        assert_eq!(decode_decimals(word(78)), None);
+        assert_eq!(decode_decimals(word(u64::MAX)), None);
+    }
+}
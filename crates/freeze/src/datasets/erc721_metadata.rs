@@ -1,4 +1,6 @@
-use super::erc20_metadata::remove_control_characters;
+use super::erc20_metadata::{
+    call_metadata_string, metadata_status, probe_token_standard, value_status, MetadataValue,
+};
 use crate::*;
 use polars::prelude::*;
 
@@ -11,10 +13,17 @@ pub struct Erc721Metadata {
     erc721: Vec<Vec<u8>>,
     name: Vec<Option<String>>,
     symbol: Vec<Option<String>>,
+    status: Vec<String>,
+    supports_erc165: Vec<bool>,
+    token_standard: Vec<Option<String>>,
     chain_id: Vec<u64>,
 }
 
 impl Dataset for Erc721Metadata {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "erc721", "name", "symbol", "status", "chain_id"])
+    }
+
     fn default_sort() -> Option<Vec<&'static str>> {
         Some(vec!["symbol", "block_number"])
     }
@@ -28,35 +37,45 @@ impl Dataset for Erc721Metadata {
     }
 }
 
+type Erc721MetadataResponse =
+    (u32, Vec<u8>, Option<MetadataValue>, Option<MetadataValue>, Option<(bool, Option<String>)>);
+
 #[async_trait::async_trait]
 impl CollectByBlock for Erc721Metadata {
-    type Response = (u32, Vec<u8>, Option<String>, Option<String>);
+    type Response = Erc721MetadataResponse;
 
-    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::Erc721Metadata)?;
         let block_number = request.ethers_block_number()?;
         let address = request.ethers_address()?;
 
-        // name
-        let call_data = FUNCTION_ERC20_NAME.clone();
-        let output = source.fetcher.call2(address, call_data, block_number).await?;
-        let name = String::from_utf8(output.to_vec()).ok().map(|s| remove_control_characters(&s));
+        let (name, symbol) = futures::join!(
+            call_metadata_string(&source, address, FUNCTION_ERC20_NAME.clone(), block_number),
+            call_metadata_string(&source, address, FUNCTION_ERC20_SYMBOL.clone(), block_number),
+        );
 
-        // symbol
-        let call_data = FUNCTION_ERC20_SYMBOL.clone();
-        let output = source.fetcher.call2(address, call_data, block_number).await?;
-        let symbol = String::from_utf8(output.to_vec()).ok().map(|s| remove_control_characters(&s));
+        let probe = if schema.has_column("supports_erc165") | schema.has_column("token_standard") {
+            Some(probe_token_standard(&source, address, block_number).await)
+        } else {
+            None
+        };
 
-        Ok((request.block_number()? as u32, request.address()?, name, symbol))
+        Ok((request.block_number()? as u32, request.address()?, name, symbol, probe))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let schema = query.schemas.get_schema(&Datatype::Erc721Metadata)?;
-        let (block, address, name, symbol) = response;
+        let (block, address, name, symbol, probe) = response;
+        let status = metadata_status(&[value_status(&name), value_status(&symbol)]);
+        let (supports_erc165, token_standard) = probe.unwrap_or_default();
         columns.n_rows += 1;
         store!(schema, columns, block_number, block);
         store!(schema, columns, erc721, address);
-        store!(schema, columns, name, name);
-        store!(schema, columns, symbol, symbol);
+        store!(schema, columns, name, name.map(|x| x.into_string()));
+        store!(schema, columns, symbol, symbol.map(|x| x.into_string()));
+        store!(schema, columns, status, status);
+        store!(schema, columns, supports_erc165, supports_erc165);
+        store!(schema, columns, token_standard, token_standard);
         Ok(())
     }
 }
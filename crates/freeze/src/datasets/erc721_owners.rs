@@ -0,0 +1,162 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// columns for erc721 owners
+#[cryo_to_df::to_df(Datatype::Erc721Owners)]
+#[derive(Default)]
+pub struct Erc721Owners {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    erc721: Vec<Vec<u8>>,
+    token_id: Vec<U256>,
+    owner: Vec<Option<Vec<u8>>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for Erc721Owners {
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["erc721", "block_number", "token_id"])
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("latest".to_string())
+    }
+
+    fn required_parameters() -> Vec<Dim> {
+        vec![Dim::Contract]
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Address, Dim::Contract)].into_iter().collect())
+    }
+}
+
+/// block number, erc721 contract, token ids and their owners
+type Erc721OwnersResponse = (u32, Vec<u8>, Vec<(U256, Option<H160>)>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for Erc721Owners {
+    type Response = Erc721OwnersResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let block_number = request.block_number()?;
+        let owners = match &query.transfer_files {
+            Some(transfer_files) => {
+                let transfers = transfer_files.read(
+                    Datatype::Erc721Transfers,
+                    &request.contract()?,
+                    block_number as u32,
+                )?;
+                replay_owners(transfers, query.token_ids.as_deref())?
+            }
+            None => call_owners(&request, &source, &query).await?,
+        };
+        Ok((block_number as u32, request.contract()?, owners))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::Erc721Owners)?;
+        let (block_number, erc721, owners) = response;
+        for (token_id, owner) in owners.into_iter() {
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, block_number);
+            store!(schema, columns, erc721, erc721.clone());
+            store!(schema, columns, token_id, token_id);
+            store!(schema, columns, owner, owner.map(|x| x.as_bytes().to_vec()));
+        }
+        Ok(())
+    }
+}
+
+impl CollectByTransaction for Erc721Owners {
+    type Response = ();
+}
+
+/// call ownerOf() for each token id, nonexistent or burned tokens give None
+async fn call_owners(
+    request: &Params,
+    source: &Source,
+    query: &Query,
+) -> R<Vec<(U256, Option<H160>)>> {
+    let token_ids = query
+        .token_ids
+        .clone()
+        .ok_or(err("must specify --token-ids or --transfers for erc721_owners"))?;
+    let block_number = request.block_number()?;
+    let contract = request.ethers_contract()?;
+    let futures = token_ids.into_iter().map(|token_id| async move {
+        let mut call_data = FUNCTION_ERC721_OWNER_OF.clone();
+        call_data.extend(ethers::abi::encode(&[ethers::abi::Token::Uint(token_id)]));
+        let output = source.fetcher.call_opt(contract, call_data, block_number.into()).await?;
+        Ok::<_, CollectError>((token_id, output.and_then(|x| bytes_to_address(&x))))
+    });
+    futures::future::join_all(futures).await.into_iter().collect()
+}
+
+/// derive owners by replaying transfers from erc721_transfers files
+fn replay_owners(
+    transfers: Vec<TransferRow>,
+    token_ids: Option<&[U256]>,
+) -> R<Vec<(U256, Option<H160>)>> {
+    let mut owners: HashMap<U256, H160> = HashMap::new();
+    for transfer in transfers.into_iter() {
+        if transfer.to_address.len() != 20 {
+            return Err(err("invalid address in erc721_transfers file"))
+        }
+        let to_address = H160::from_slice(&transfer.to_address);
+        if to_address.is_zero() {
+            owners.remove(&transfer.value);
+        } else {
+            owners.insert(transfer.value, to_address);
+        }
+    }
+
+    let owners = match token_ids {
+        Some(token_ids) => {
+            token_ids.iter().map(|token_id| (*token_id, owners.get(token_id).cloned())).collect()
+        }
+        None => owners.into_iter().map(|(token_id, owner)| (token_id, Some(owner))).collect(),
+    };
+    Ok(owners)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(from: u64, to: u64, token_id: u64) -> TransferRow {
+        TransferRow {
+            block_number: 0,
+            transaction_index: 0,
+            log_index: 0,
+            from_address: H160::from_low_u64_be(from).as_bytes().to_vec(),
+            to_address: H160::from_low_u64_be(to).as_bytes().to_vec(),
+            value: U256::from(token_id),
+        }
+    }
+
+    #[test]
+    fn test_replay_owners() {
+        let address = H160::from_low_u64_be;
+        // mint 1 and 2 to 1, 1 sends 1 to 2, 2 burns 1
+        let transfers =
+            vec![transfer(0, 1, 1), transfer(0, 1, 2), transfer(1, 2, 1), transfer(2, 0, 1)];
+        let mut owners = replay_owners(transfers.clone(), None).unwrap();
+        owners.sort();
+        assert_eq!(owners, vec![(U256::from(2), Some(address(1)))]);
+
+        let owners = replay_owners(transfers[..3].to_vec(), None).unwrap();
+        assert_eq!(owners.len(), 2);
+        assert!(owners.contains(&(U256::from(1), Some(address(2)))));
+
+        // requested token ids are kept in order, burned or unminted tokens have no owner
+        let token_ids = [3, 2, 1].map(U256::from);
+        let owners = replay_owners(transfers, Some(&token_ids)).unwrap();
+        let expected =
+            vec![(U256::from(3), None), (U256::from(2), Some(address(1))), (U256::from(1), None)];
+        assert_eq!(owners, expected);
+    }
+}
@@ -13,6 +13,7 @@ pub struct EthCalls {
     call_data_hash: Vec<Vec<u8>>,
     output_data: Vec<Vec<u8>>,
     output_data_hash: Vec<Vec<u8>>,
+    overrides_hash: Vec<Option<Vec<u8>>>,
     chain_id: Vec<u64>,
 }
 
@@ -45,29 +46,112 @@ type EthCallsResponse = (u32, Vec<u8>, Vec<u8>, Vec<u8>);
 impl CollectByBlock for EthCalls {
     type Response = EthCallsResponse;
 
-    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
-        let transaction = TransactionRequest {
-            to: Some(request.ethers_contract()?.into()),
-            data: Some(request.call_data()?.into()),
-            ..Default::default()
-        };
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
         let number = request.block_number()?;
-        let output = source.fetcher.call(transaction, number.into()).await?;
+        let (contract, call_data) = (request.ethers_contract()?, request.call_data()?);
+        let output = match &query.call_overrides {
+            Some(overrides) => {
+                let transaction = TransactionRequest {
+                    to: Some(contract.into()),
+                    data: Some(call_data.into()),
+                    ..Default::default()
+                };
+                source
+                    .fetcher
+                    .call_with_overrides(
+                        transaction,
+                        number.into(),
+                        &overrides.state_overrides,
+                        &overrides.block_overrides,
+                    )
+                    .await?
+            }
+            None => source.fetcher.call2(contract, call_data, number.into()).await?,
+        };
         Ok((number as u32, request.contract()?, request.call_data()?, output.to_vec()))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let schema = query.schemas.get_schema(&Datatype::EthCalls)?;
-        process_eth_call(response, columns, schema);
+        let overrides_hash = query.call_overrides.as_ref().map(|x| x.hash.clone());
+        process_eth_call(response, overrides_hash, columns, schema);
         Ok(())
     }
 }
 
+/// state and block overrides applied to eth_calls and trace_calls
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CallOverrides {
+    /// state override set, with balance, nonce, code, and state or stateDiff of each address
+    #[serde(default)]
+    pub state_overrides: spoof::State,
+    /// block overrides, such as time and baseFee
+    #[serde(default)]
+    pub block_overrides: Option<BlockOverrides>,
+    /// hash of the overrides, to label the calls that use them
+    #[serde(skip)]
+    pub hash: Vec<u8>,
+}
+
+/// block overrides of geth's eth_call and debug_traceCall
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+    /// block number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<U64>,
+    /// difficulty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<U256>,
+    /// timestamp
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<U64>,
+    /// gas limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<U64>,
+    /// fee recipient
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coinbase: Option<H160>,
+    /// prevrandao
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub random: Option<H256>,
+    /// base fee per gas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee: Option<U256>,
+}
+
+impl CallOverrides {
+    /// load overrides from json file, ex: `{"stateOverrides": {...}, "blockOverrides": {...}}`
+    pub fn load_file(path: &str) -> Result<CallOverrides, ParseError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|_| ParseError::ParseError(format!("could not read {}", path)))?;
+        Self::parse(&contents)
+    }
+
+    fn parse(contents: &str) -> Result<CallOverrides, ParseError> {
+        let value: serde_json::Value = serde_json::from_str(contents)
+            .map_err(|e| ParseError::ParseError(format!("invalid call overrides: {}", e)))?;
+        let mut overrides: CallOverrides = serde_json::from_value(value.clone())
+            .map_err(|e| ParseError::ParseError(format!("invalid call overrides: {}", e)))?;
+        // hash the parsed json so that formatting of the file does not change the hash
+        let canonical = serde_json::to_vec(&value)
+            .map_err(|_| ParseError::ParseError("cannot serialize call overrides".to_string()))?;
+        overrides.hash = ethers_core::utils::keccak256(canonical).to_vec();
+        Ok(overrides)
+    }
+}
+
 impl CollectByTransaction for EthCalls {
     type Response = ();
 }
 
-fn process_eth_call(response: EthCallsResponse, columns: &mut EthCalls, schema: &Table) {
+fn process_eth_call(
+    response: EthCallsResponse,
+    overrides_hash: Option<Vec<u8>>,
+    columns: &mut EthCalls,
+    schema: &Table,
+) {
     let (block_number, contract_address, call_data, output_data) = response;
     columns.n_rows += 1;
     store!(schema, columns, block_number, block_number);
@@ -76,4 +160,31 @@ fn process_eth_call(response: EthCallsResponse, columns: &mut EthCalls, schema:
     store!(schema, columns, call_data_hash, ethers_core::utils::keccak256(call_data).into());
     store!(schema, columns, output_data, output_data.to_vec());
     store!(schema, columns, output_data_hash, ethers_core::utils::keccak256(output_data).into());
+    store!(schema, columns, overrides_hash, overrides_hash);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_call_overrides() {
+        let address = "0x6b175474e89094c44da98b954eedeac495271d0f";
+        let contents = format!(
+            r#"{{"stateOverrides": {{"{}": {{"balance": "0x1", "stateDiff": {{}}}}}},
+                "blockOverrides": {{"time": "0x64", "baseFee": "0x0"}}}}"#,
+            address
+        );
+        let mut overrides = CallOverrides::parse(&contents).unwrap();
+        let account = overrides.state_overrides.account(address.parse::<H160>().unwrap());
+        assert_eq!(account.balance, Some(U256::one()));
+        assert!(matches!(account.storage, Some(spoof::Storage::Diff(_))));
+        let block_overrides = overrides.block_overrides.unwrap();
+        assert_eq!(block_overrides.time, Some(U64::from(100)));
+
+        // hash ignores formatting
+        let compact = contents.split_whitespace().collect::<String>();
+        assert_eq!(CallOverrides::parse(&compact).unwrap().hash, overrides.hash);
+        assert!(CallOverrides::parse(r#"{"stateOverride": {}}"#).is_err());
+    }
 }
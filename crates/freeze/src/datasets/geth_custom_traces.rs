@@ -0,0 +1,229 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// columns for geth custom traces
+#[cryo_to_df::to_df(Datatype::GethCustomTraces)]
+#[derive(Default)]
+pub struct GethCustomTraces {
+    n_rows: u64,
+    block_number: Vec<Option<u32>>,
+    transaction_hash: Vec<Option<Vec<u8>>>,
+    transaction_index: Vec<u32>,
+    result: Vec<Option<String>>,
+    trace_cols: BTreeMap<String, Vec<Option<String>>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for GethCustomTraces {
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index"])
+    }
+}
+
+/// Javascript or native geth tracer, and how to flatten its results into columns
+#[derive(Clone, Debug)]
+pub struct CustomTracer {
+    /// javascript source of tracer, or name of native tracer
+    pub tracer: String,
+    /// tracerConfig passed to tracer
+    pub config: Option<Value>,
+    /// output columns and the json paths of the result they are read from
+    pub columns: Vec<(String, String)>,
+}
+
+impl CustomTracer {
+    /// create tracer from a js file or native tracer name, a json config or config file, and
+    /// column mappings in format `column=json.path`
+    pub fn new(
+        tracer: &str,
+        config: Option<&str>,
+        columns: Option<&[String]>,
+    ) -> Result<Self, ParseError> {
+        let tracer = if std::path::Path::new(tracer).is_file() {
+            std::fs::read_to_string(tracer)
+                .map_err(|_| ParseError::ParseError(format!("could not read {}", tracer)))?
+        } else if tracer.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            tracer.to_string()
+        } else {
+            return Err(ParseError::ParseError(format!("tracer file {} not found", tracer)))
+        };
+
+        let config = match config {
+            Some(config) => {
+                let contents = if std::path::Path::new(config).is_file() {
+                    std::fs::read_to_string(config)
+                        .map_err(|_| ParseError::ParseError(format!("could not read {}", config)))?
+                } else {
+                    config.to_string()
+                };
+                let config = serde_json::from_str(&contents)
+                    .map_err(|e| ParseError::ParseError(format!("invalid tracer config: {}", e)))?;
+                Some(config)
+            }
+            None => None,
+        };
+
+        let mut mappings = Vec::new();
+        for column in columns.unwrap_or_default() {
+            match column.split_once('=') {
+                Some((name, _)) if GethCustomTraces::column_types().contains_key(name) => {
+                    return Err(ParseError::ParseError(format!(
+                        "tracer column {} conflicts with a built-in column",
+                        name
+                    )))
+                }
+                Some((name, _)) if mappings.iter().any(|(other, _)| other == name) => {
+                    return Err(ParseError::ParseError(format!(
+                        "tracer column {} is given more than once",
+                        name
+                    )))
+                }
+                Some((name, path)) if !name.is_empty() => {
+                    mappings.push((name.to_string(), path.to_string()))
+                }
+                _ => {
+                    return Err(ParseError::ParseError(format!(
+                        "tracer column {} should be in format column=json.path",
+                        column
+                    )))
+                }
+            }
+        }
+        Ok(Self { tracer, config, columns: mappings })
+    }
+
+    fn options(&self) -> GethDebugTracingOptions {
+        GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::JsTracer(self.tracer.clone())),
+            tracer_config: self.config.clone().map(GethDebugTracerConfig::JsTracer),
+            ..Default::default()
+        }
+    }
+}
+
+type BlockTxsResults = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<Value>);
+
+fn get_tracer(query: &Query) -> R<&CustomTracer> {
+    query.custom_tracer.as_ref().ok_or(err("geth_custom_traces requires a tracer"))
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for GethCustomTraces {
+    type Response = BlockTxsResults;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::GethCustomTraces)?;
+        let include_txs = schema.has_column("transaction_hash");
+        let options = get_tracer(&query)?.options();
+        let block_number = request.block_number()? as u32;
+        source.fetcher.geth_debug_trace_block_json(block_number, options, include_txs).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_custom_traces(response, columns, query)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for GethCustomTraces {
+    type Response = BlockTxsResults;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::GethCustomTraces)?;
+        let include_block_number = schema.has_column("block_number");
+        let options = get_tracer(&query)?.options();
+        let transaction_hash = request.transaction_hash()?;
+        source
+            .fetcher
+            .geth_debug_trace_transaction_json(transaction_hash, options, include_block_number)
+            .await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_custom_traces(response, columns, query)
+    }
+}
+
+fn process_custom_traces(
+    response: BlockTxsResults,
+    columns: &mut GethCustomTraces,
+    query: &Query,
+) -> R<()> {
+    let (block_number, txs, traces) = response;
+    let schema = query.schemas.get_schema(&Datatype::GethCustomTraces)?;
+    let tracer = get_tracer(query)?;
+    // mapped columns are created even if there are no transactions, to keep chunks consistent
+    for (name, _) in tracer.columns.iter() {
+        columns.trace_cols.entry(name.clone()).or_default();
+    }
+    for (tx_index, (tx, result)) in txs.into_iter().zip(traces).enumerate() {
+        for (name, path) in tracer.columns.iter() {
+            let value = json_path(&result, path).and_then(json_to_string);
+            columns.trace_cols.entry(name.clone()).or_default().push(value);
+        }
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, block_number);
+        store!(schema, columns, transaction_hash, tx);
+        store!(schema, columns, transaction_index, tx_index as u32);
+        store!(schema, columns, result, json_to_string(&result));
+    }
+    Ok(())
+}
+
+/// get value at a json path, ex: `$.calls[0].to` or `calls.0.to`
+fn json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.trim_start_matches('$').replace('[', ".").replace(']', "");
+    let mut value = value;
+    for key in path.split('.').filter(|key| !key.is_empty()) {
+        value = match value {
+            Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+            Value::Object(map) => map.get(key)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// strings are given without quotes, other values as json
+fn json_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_path() {
+        let value: Value =
+            serde_json::from_str(r#"{"gas": 21000, "calls": [{"to": "0x01", "ok": true}]}"#)
+                .unwrap();
+        let get = |path| json_path(&value, path).and_then(json_to_string);
+        assert_eq!(get("$.gas"), Some("21000".to_string()));
+        assert_eq!(get("$.calls[0].to"), Some("0x01".to_string()));
+        assert_eq!(get("calls.0.ok"), Some("true".to_string()));
+        assert_eq!(get("$.calls[1].to"), None);
+        assert_eq!(get("$"), Some(value.to_string()));
+    }
+    #[test]
+    fn test_tracer_columns() {
+        let new = |columns: &[&str]| {
+            let columns: Vec<String> = columns.iter().map(|x| x.to_string()).collect();
+            CustomTracer::new("callTracer", None, Some(&columns))
+        };
+        let tracer = new(&["gas_used=gasUsed", "to=to"]).unwrap();
+        assert_eq!(tracer.columns[0], ("gas_used".to_string(), "gasUsed".to_string()));
+        assert!(new(&["result=output"]).is_err());
+        assert!(new(&["block_number=blockNumber"]).is_err());
+        assert!(new(&["to=to", "to=from"]).is_err());
+        assert!(new(&["to"]).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+
+/// columns for geth opcode traces
+#[cryo_to_df::to_df(Datatype::GethOpcodeTraces)]
+#[derive(Default)]
+pub struct GethOpcodeTraces {
+    n_rows: usize,
+    block_number: Vec<Option<u32>>,
+    transaction_hash: Vec<Option<Vec<u8>>>,
+    transaction_index: Vec<u32>,
+    step: Vec<u32>,
+    pc: Vec<u64>,
+    op: Vec<String>,
+    cost: Vec<u64>,
+    gas: Vec<u64>,
+    used: Vec<u64>,
+    depth: Vec<u32>,
+    refund: Vec<Option<u64>>,
+    error: Vec<Option<String>>,
+    memory: Vec<Option<Vec<u8>>>,
+    stack: Vec<Option<String>>,
+    storage: Vec<Option<String>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for GethOpcodeTraces {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "step",
+            "pc",
+            "op",
+            "cost",
+            "gas",
+            "depth",
+            "chain_id",
+        ])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "step"])
+    }
+}
+
+type BlockTxsFrames = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<DefaultFrame>);
+
+/// struct logger options, capturing memory, stack, and storage only if their columns are used
+fn struct_log_options(schema: &Table) -> GethDebugTracingOptions {
+    GethDebugTracingOptions {
+        enable_memory: Some(schema.has_column("memory")),
+        disable_stack: Some(!schema.has_column("stack")),
+        disable_storage: Some(!schema.has_column("storage")),
+        ..Default::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for GethOpcodeTraces {
+    type Response = BlockTxsFrames;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::GethOpcodeTraces)?;
+        let include_txs = schema.has_column("transaction_hash");
+        let options = struct_log_options(schema);
+        let block_number = request.block_number()? as u32;
+        source.fetcher.geth_debug_trace_block_opcodes(block_number, options, include_txs).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_struct_logs(response, columns, &query.schemas)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for GethOpcodeTraces {
+    type Response = BlockTxsFrames;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::GethOpcodeTraces)?;
+        let include_block_number = schema.has_column("block_number");
+        let options = struct_log_options(schema);
+        source
+            .fetcher
+            .geth_debug_trace_transaction_opcodes(
+                request.transaction_hash()?,
+                options,
+                include_block_number,
+            )
+            .await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_struct_logs(response, columns, &query.schemas)
+    }
+}
+
+fn process_struct_logs(
+    response: BlockTxsFrames,
+    columns: &mut GethOpcodeTraces,
+    schemas: &Schemas,
+) -> R<()> {
+    let (block_number, txs, frames) = response;
+    let schema = schemas.get(&Datatype::GethOpcodeTraces).ok_or(err("schema not provided"))?;
+    for (tx_index, (tx, frame)) in txs.into_iter().zip(frames).enumerate() {
+        for (step, log) in frame.struct_logs.into_iter().enumerate() {
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, block_number);
+            store!(schema, columns, transaction_hash, tx.clone());
+            store!(schema, columns, transaction_index, tx_index as u32);
+            store!(schema, columns, step, step as u32);
+            store!(schema, columns, pc, log.pc);
+            store!(schema, columns, op, log.op);
+            store!(schema, columns, cost, log.gas_cost);
+            store!(schema, columns, gas, log.gas);
+            store!(schema, columns, used, log.gas.saturating_sub(log.gas_cost));
+            store!(schema, columns, depth, log.depth as u32);
+            store!(schema, columns, refund, log.refund_counter);
+            store!(schema, columns, error, log.error);
+            store!(schema, columns, memory, log.memory.map(|x| memory_to_bytes(&x)));
+            store!(schema, columns, stack, log.stack.map(|x| stack_to_string(&x)));
+            store!(schema, columns, storage, log.storage.map(|x| storage_to_string(&x)));
+        }
+    }
+    Ok(())
+}
+
+/// concatenate the 32 byte memory words of a struct log
+fn memory_to_bytes(words: &[String]) -> Vec<u8> {
+    words
+        .iter()
+        .flat_map(|word| {
+            let word = format!("0x{}", word.trim_start_matches("0x"));
+            prefix_hex::decode::<Vec<u8>>(word).unwrap_or_default()
+        })
+        .collect()
+}
+
+/// stack items from bottom to top, ex: `[0x80,0x4]`
+fn stack_to_string(stack: &[U256]) -> String {
+    let items: Vec<String> = stack.iter().map(|x| format!("{:#x}", x)).collect();
+    format!("[{}]", items.join(","))
+}
+
+/// storage accessed so far by the executing contract, ex: `{0x..:0x..}`
+fn storage_to_string(storage: &std::collections::BTreeMap<H256, H256>) -> String {
+    let items: Vec<String> =
+        storage.iter().map(|(slot, value)| format!("{:?}:{:?}", slot, value)).collect();
+    format!("{{{}}}", items.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_struct_log_values() {
+        let memory = vec![format!("{}80", "00".repeat(31)), format!("0x{}", "ff".repeat(32))];
+        let bytes = memory_to_bytes(&memory);
+        assert_eq!(bytes.len(), 64);
+        assert_eq!((bytes[31], bytes[32]), (0x80, 0xff));
+
+        let stack = vec![U256::from(0x80), U256::from(4)];
+        assert_eq!(stack_to_string(&stack), "[0x80,0x4]");
+    }
+}
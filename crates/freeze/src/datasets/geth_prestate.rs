@@ -0,0 +1,138 @@
+use crate::*;
+use ethers::prelude::*;
+use ethers_core::utils::keccak256;
+use polars::prelude::*;
+use std::collections::BTreeMap;
+
+/// columns for geth prestate, the state read by each transaction
+///
+/// each account touched gives a row with its balance, nonce, and code hash, and each storage
+/// slot read gives a row with its slot and value. prestates omit zero nonces, which are stored
+/// as 0 on account rows
+#[cryo_to_df::to_df(Datatype::GethPrestate)]
+#[derive(Default)]
+pub struct GethPrestate {
+    n_rows: u64,
+    block_number: Vec<Option<u32>>,
+    transaction_index: Vec<u32>,
+    transaction_hash: Vec<Option<Vec<u8>>>,
+    address: Vec<Vec<u8>>,
+    balance: Vec<Option<U256>>,
+    nonce: Vec<Option<u64>>,
+    code_hash: Vec<Option<Vec<u8>>>,
+    slot: Vec<Option<Vec<u8>>>,
+    value: Vec<Option<Vec<u8>>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for GethPrestate {
+    fn aliases() -> Vec<&'static str> {
+        vec!["geth_read_sets"]
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "address", "slot"])
+    }
+}
+
+type BlockTxsPrestates = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<BTreeMap<H160, AccountState>>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for GethPrestate {
+    type Response = BlockTxsPrestates;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::GethPrestate)?;
+        let include_txs = schema.has_column("transaction_hash");
+        let block_number = request.block_number()? as u32;
+        source.fetcher.geth_debug_trace_block_prestate(block_number, include_txs).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_prestates(response, columns, &query.schemas)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for GethPrestate {
+    type Response = BlockTxsPrestates;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::GethPrestate)?;
+        let include_block_number = schema.has_column("block_number");
+        source
+            .fetcher
+            .geth_debug_trace_transaction_prestate(
+                request.transaction_hash()?,
+                include_block_number,
+            )
+            .await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_prestates(response, columns, &query.schemas)
+    }
+}
+
+fn process_prestates(
+    response: BlockTxsPrestates,
+    columns: &mut GethPrestate,
+    schemas: &Schemas,
+) -> R<()> {
+    let (block_number, txs, prestates) = response;
+    let schema = schemas.get(&Datatype::GethPrestate).ok_or(err("schema not provided"))?;
+    for (tx_index, (tx, prestate)) in txs.into_iter().zip(prestates).enumerate() {
+        let transaction_index = tx_index as u32;
+        for (address, account) in prestate.into_iter() {
+            let address = address.as_bytes().to_vec();
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, block_number);
+            store!(schema, columns, transaction_index, transaction_index);
+            store!(schema, columns, transaction_hash, tx.clone());
+            store!(schema, columns, address, address.clone());
+            store!(schema, columns, balance, account.balance);
+            store!(schema, columns, nonce, Some(account.nonce.unwrap_or_default().as_u64()));
+            store!(schema, columns, code_hash, Some(code_hash(&account.code)?));
+            store!(schema, columns, slot, None);
+            store!(schema, columns, value, None);
+
+            for (slot, value) in account.storage.unwrap_or_default().into_iter() {
+                columns.n_rows += 1;
+                store!(schema, columns, block_number, block_number);
+                store!(schema, columns, transaction_index, transaction_index);
+                store!(schema, columns, transaction_hash, tx.clone());
+                store!(schema, columns, address, address.clone());
+                store!(schema, columns, balance, None);
+                store!(schema, columns, nonce, None);
+                store!(schema, columns, code_hash, None);
+                store!(schema, columns, slot, Some(slot.as_bytes().to_vec()));
+                store!(schema, columns, value, Some(value.as_bytes().to_vec()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// hash of account code, prestates omit the code of accounts without code
+fn code_hash(code: &Option<String>) -> R<Vec<u8>> {
+    let code = match code {
+        Some(code) => prefix_hex::decode::<Vec<u8>>(code).map_err(|_| err("invalid code"))?,
+        None => vec![],
+    };
+    Ok(keccak256(code).to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_hash() {
+        // hash of empty code, as given by eth_getProof for accounts without code
+        let empty = "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+        assert_eq!(code_hash(&None).unwrap(), prefix_hex::decode::<Vec<u8>>(empty).unwrap());
+        assert_eq!(code_hash(&Some("0x".to_string())).unwrap(), code_hash(&None).unwrap());
+        assert!(code_hash(&Some("0xzz".to_string())).is_err());
+    }
+}
@@ -13,10 +13,28 @@ pub struct GethStorageDiffs {
     pub(crate) slot: Vec<Vec<u8>>,
     pub(crate) from_value: Vec<Vec<u8>>,
     pub(crate) to_value: Vec<Vec<u8>>,
+    pub(crate) variable: Vec<Option<String>>,
+    pub(crate) mapping_keys: Vec<Option<String>>,
+    pub(crate) value_type: Vec<Option<String>>,
+    pub(crate) decoded_from_value: Vec<Option<String>>,
+    pub(crate) decoded_to_value: Vec<Option<String>>,
     pub(crate) chain_id: Vec<u64>,
 }
 
-impl Dataset for GethStorageDiffs {}
+impl Dataset for GethStorageDiffs {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "transaction_hash",
+            "address",
+            "slot",
+            "from_value",
+            "to_value",
+            "chain_id",
+        ])
+    }
+}
 
 #[async_trait::async_trait]
 impl CollectByBlock for GethStorageDiffs {
@@ -0,0 +1,213 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+
+/// columns for logs of geth call traces
+#[cryo_to_df::to_df(Datatype::GethTraceLogs)]
+#[derive(Default)]
+pub struct GethTraceLogs {
+    n_rows: u64,
+    block_number: Vec<Option<u32>>,
+    transaction_hash: Vec<Option<Vec<u8>>>,
+    transaction_index: Vec<u32>,
+    trace_address: Vec<String>,
+    frame_log_index: Vec<u32>,
+    address: Vec<Option<Vec<u8>>>,
+    topic0: Vec<Option<Vec<u8>>>,
+    topic1: Vec<Option<Vec<u8>>>,
+    topic2: Vec<Option<Vec<u8>>>,
+    topic3: Vec<Option<Vec<u8>>>,
+    data: Vec<Vec<u8>>,
+    reverted: Vec<bool>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for GethTraceLogs {
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "trace_address", "frame_log_index"])
+    }
+}
+
+/// js tracer nesting the logs of each call frame, including the logs of failed frames
+///
+/// geth's callTracer with `withLog` clears the logs of failed frames, so logs are instead
+/// recorded at each LOG opcode, and dropped again if the opcode itself faults
+const FRAME_LOGS_TRACER: &str = r#"{
+    frames: [{calls: [], logs: []}],
+    step: function(log, db) {
+        var op = log.op.toNumber();
+        if (op < 0xa0 || op > 0xa4) { return; }
+        var offset = log.stack.peek(0).valueOf();
+        var size = log.stack.peek(1).valueOf();
+        var topics = [];
+        for (var i = 0; i < op - 0xa0; i++) {
+            var topic = log.stack.peek(2 + i).toString(16);
+            while (topic.length < 64) { topic = '0' + topic; }
+            topics.push('0x' + topic);
+        }
+        this.frames[this.frames.length - 1].logs.push({
+            address: toHex(log.contract.getAddress()),
+            topics: topics,
+            data: toHex(log.memory.slice(offset, offset + size)),
+        });
+    },
+    fault: function(log, db) {
+        var op = log.op.toNumber();
+        if (op >= 0xa0 && op <= 0xa4) { this.frames[this.frames.length - 1].logs.pop(); }
+    },
+    enter: function(frame) { this.frames.push({calls: [], logs: []}); },
+    exit: function(result) {
+        var frame = this.frames.pop();
+        if (result.getError() !== undefined) { frame.error = result.getError(); }
+        this.frames[this.frames.length - 1].calls.push(frame);
+    },
+    result: function(ctx, db) {
+        var frame = this.frames[0];
+        if (ctx.error !== undefined) { frame.error = ctx.error; }
+        return frame;
+    },
+}"#;
+
+/// call frame as returned by FRAME_LOGS_TRACER
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+struct LogFrame {
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    calls: Vec<LogFrame>,
+    #[serde(default)]
+    logs: Vec<CallLogFrame>,
+}
+
+fn tracer_options() -> GethDebugTracingOptions {
+    GethDebugTracingOptions {
+        tracer: Some(GethDebugTracerType::JsTracer(FRAME_LOGS_TRACER.to_string())),
+        ..Default::default()
+    }
+}
+
+type BlockTxsFrames = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<serde_json::Value>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for GethTraceLogs {
+    type Response = BlockTxsFrames;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::GethTraceLogs)?;
+        let include_transaction = schema.has_column("transaction_hash");
+        let block_number = request.block_number()? as u32;
+        source
+            .fetcher
+            .geth_debug_trace_block_json(block_number, tracer_options(), include_transaction)
+            .await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_trace_logs(response, columns, &query.schemas)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for GethTraceLogs {
+    type Response = BlockTxsFrames;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::GethTraceLogs)?;
+        let include_block_number = schema.has_column("block_number");
+        source
+            .fetcher
+            .geth_debug_trace_transaction_json(
+                request.transaction_hash()?,
+                tracer_options(),
+                include_block_number,
+            )
+            .await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_trace_logs(response, columns, &query.schemas)
+    }
+}
+
+fn process_trace_logs(
+    response: BlockTxsFrames,
+    columns: &mut GethTraceLogs,
+    schemas: &Schemas,
+) -> R<()> {
+    let (block_number, txs, frames) = response;
+    let schema = schemas.get(&Datatype::GethTraceLogs).ok_or(err("schema not provided"))?;
+    for (tx_index, (tx, frame)) in txs.into_iter().zip(frames).enumerate() {
+        let frame: LogFrame = serde_json::from_value(frame)
+            .map_err(|_| err("cannot deserialize trace log frames"))?;
+        let tx_info = (block_number, tx, tx_index as u32);
+        process_frame(frame, &tx_info, vec![], false, columns, schema);
+    }
+    Ok(())
+}
+
+/// add the logs of a frame and its subcalls, logs are reverted if any enclosing frame reverted
+fn process_frame(
+    frame: LogFrame,
+    tx_info: &(Option<u32>, Option<Vec<u8>>, u32),
+    trace_address: Vec<u32>,
+    parent_reverted: bool,
+    columns: &mut GethTraceLogs,
+    schema: &Table,
+) {
+    let (block_number, tx, tx_index) = tx_info;
+    let reverted = parent_reverted || frame.error.is_some();
+    let address_string = trace_address.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(" ");
+    for (log_index, log) in frame.logs.into_iter().enumerate() {
+        let topics = log.topics.unwrap_or_default();
+        let topic = |i: usize| topics.get(i).map(|x| x.as_bytes().to_vec());
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, *block_number);
+        store!(schema, columns, transaction_hash, tx.clone());
+        store!(schema, columns, transaction_index, *tx_index);
+        store!(schema, columns, trace_address, address_string.clone());
+        store!(schema, columns, frame_log_index, log_index as u32);
+        store!(schema, columns, address, log.address.map(|x| x.as_bytes().to_vec()));
+        store!(schema, columns, topic0, topic(0));
+        store!(schema, columns, topic1, topic(1));
+        store!(schema, columns, topic2, topic(2));
+        store!(schema, columns, topic3, topic(3));
+        store!(schema, columns, data, log.data.map(|x| x.to_vec()).unwrap_or_default());
+        store!(schema, columns, reverted, reverted);
+    }
+    for (index, subcall) in frame.calls.into_iter().enumerate() {
+        let mut sub_trace_address = trace_address.clone();
+        sub_trace_address.push(index as u32);
+        process_frame(subcall, tx_info, sub_trace_address, reverted, columns, schema);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_frame_reverted() {
+        let log = serde_json::json!([{"address": "0x0000000000000000000000000000000000000001", "topics": [], "data": "0x"}]);
+        // frames as returned by the tracer, which omits the error of successful frames
+        let frame = |error: Option<&str>, calls: serde_json::Value| {
+            let mut frame = serde_json::json!({"calls": calls, "logs": log});
+            if let Some(error) = error {
+                frame["error"] = error.into();
+            }
+            frame
+        };
+        // root succeeds, its first subcall reverts, so the nested call's log is reverted too
+        let grandchild = frame(None, serde_json::json!([]));
+        let child = frame(Some("execution reverted"), serde_json::json!([grandchild]));
+        let sibling = frame(None, serde_json::json!([]));
+        let root: LogFrame =
+            serde_json::from_value(frame(None, serde_json::json!([child, sibling]))).unwrap();
+
+        let schema = test_schema(Datatype::GethTraceLogs, &[]);
+        let mut columns = GethTraceLogs::default();
+        process_frame(root, &(Some(1), None, 0), vec![], false, &mut columns, &schema);
+        assert_eq!(columns.trace_address, vec!["", "0", "0 0", "1"]);
+        assert_eq!(columns.reverted, vec![false, true, true, false]);
+    }
+}
@@ -20,11 +20,33 @@ pub struct GethTraces {
     transaction_hash: Vec<Option<Vec<u8>>>,
     transaction_index: Vec<u32>,
     trace_address: Vec<String>,
+    function_name: Vec<Option<String>>,
+    revert_reason: Vec<Option<String>>,
+    function_cols: std::collections::HashMap<String, Vec<Option<ethers_core::abi::Token>>>,
     chain_id: Vec<u64>,
 }
 
 #[async_trait::async_trait]
-impl Dataset for GethTraces {}
+impl Dataset for GethTraces {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "typ",
+            "from_address",
+            "to_address",
+            "value",
+            "gas",
+            "gas_used",
+            "input",
+            "output",
+            "error",
+            "block_number",
+            "transaction_hash",
+            "transaction_index",
+            "trace_address",
+            "chain_id",
+        ])
+    }
+}
 
 #[async_trait::async_trait]
 impl CollectByBlock for GethTraces {
@@ -34,11 +56,18 @@ impl CollectByBlock for GethTraces {
         let schema = query.schemas.get_schema(&Datatype::GethTraces)?;
         let include_transaction = schema.has_column("block_number");
         let block_number = request.block_number()? as u32;
-        source.fetcher.geth_debug_trace_block_calls(block_number, include_transaction).await
+        if query.flat_call_tracer {
+            source
+                .fetcher
+                .geth_debug_trace_block_flat_calls(block_number, include_transaction)
+                .await
+        } else {
+            source.fetcher.geth_debug_trace_block_calls(block_number, include_transaction).await
+        }
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
-        process_geth_traces(response, columns, &query.schemas)
+        process_geth_traces(response, columns, query)
     }
 }
 
@@ -49,26 +78,36 @@ impl CollectByTransaction for GethTraces {
     async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
         let schema = query.schemas.get_schema(&Datatype::GethTraces)?;
         let include_block_number = schema.has_column("block_number");
-        source
-            .fetcher
-            .geth_debug_trace_transaction_calls(request.transaction_hash()?, include_block_number)
-            .await
+        let transaction_hash = request.transaction_hash()?;
+        if query.flat_call_tracer {
+            source
+                .fetcher
+                .geth_debug_trace_transaction_flat_calls(transaction_hash, include_block_number)
+                .await
+        } else {
+            source
+                .fetcher
+                .geth_debug_trace_transaction_calls(transaction_hash, include_block_number)
+                .await
+        }
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
-        process_geth_traces(response, columns, &query.schemas)
+        process_geth_traces(response, columns, query)
     }
 }
 
 fn process_geth_traces(
     traces: (Option<u32>, Vec<Option<Vec<u8>>>, Vec<CallFrame>),
     columns: &mut GethTraces,
-    schemas: &Schemas,
+    query: &Query,
 ) -> R<()> {
     let (block_number, txs, traces) = traces;
-    let schema = schemas.get(&Datatype::GethTraces).ok_or(err("schema for geth_traces missing"))?;
+    let schema =
+        query.schemas.get(&Datatype::GethTraces).ok_or(err("schema for geth_traces missing"))?;
     for (tx_index, (tx, trace)) in txs.into_iter().zip(traces).enumerate() {
-        process_trace(trace, columns, schema, &block_number, &tx, tx_index as u32, vec![])?
+        let tx_index = tx_index as u32;
+        process_trace(trace, columns, schema, &block_number, &tx, tx_index, vec![])?
     }
     Ok(())
 }
@@ -83,6 +122,24 @@ fn process_trace(
     trace_address: Vec<u32>,
 ) -> R<()> {
     columns.n_rows += 1;
+    let function_name = match &schema.function_decoder {
+        Some(decoder) => {
+            // create frames carry init code rather than calldata
+            let (input, output) = if trace.typ.starts_with("CREATE") {
+                (None, None)
+            } else {
+                (Some(trace.input.as_ref()), trace.output.as_ref().map(|x| x.as_ref()))
+            };
+            decoder.decode_call(&mut columns.function_cols, input, output)
+        }
+        None => None,
+    };
+    store!(schema, columns, function_name, function_name);
+    let revert_reason = match (&trace.error, &trace.output) {
+        (Some(_), Some(output)) => decode_revert_reason(output, schema.function_decoder.as_ref()),
+        _ => None,
+    };
+    store!(schema, columns, revert_reason, revert_reason);
     store!(schema, columns, typ, trace.typ);
     store!(schema, columns, from_address, trace.from.as_bytes().to_vec());
     store!(schema, columns, to_address, noa_to_vec_u8(trace.to)?);
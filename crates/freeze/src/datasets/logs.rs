@@ -17,6 +17,8 @@ pub struct Logs {
     topic2: Vec<Option<Vec<u8>>>,
     topic3: Vec<Option<Vec<u8>>>,
     data: Vec<Vec<u8>>,
+    event_signature: Vec<Option<String>>,
+    signature_unique: Vec<Option<bool>>,
     event_cols: std::collections::HashMap<String, Vec<ethers_core::abi::Token>>,
     chain_id: Vec<u64>,
 }
@@ -27,6 +29,22 @@ impl Dataset for Logs {
         vec!["events"]
     }
 
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "log_index",
+            "transaction_hash",
+            "address",
+            "topic0",
+            "topic1",
+            "topic2",
+            "topic3",
+            "data",
+            "chain_id",
+        ])
+    }
+
     fn optional_parameters() -> Vec<Dim> {
         vec![Dim::Contract, Dim::Topic0, Dim::Topic1, Dim::Topic2, Dim::Topic3]
     }
@@ -77,6 +95,12 @@ fn process_logs(logs: Vec<Log>, columns: &mut Logs, schema: &Table) -> R<()> {
             store!(schema, columns, transaction_hash, tx.as_bytes().to_vec());
             store!(schema, columns, address, log.address.as_bytes().to_vec());
             store!(schema, columns, data, log.data.to_vec());
+            let (event_signature, signature_unique) = match &schema.signature_database {
+                Some(database) => database.event_signature(log.topics.first()),
+                None => (None, None),
+            };
+            store!(schema, columns, event_signature, event_signature);
+            store!(schema, columns, signature_unique, signature_unique);
 
             // topics
             for i in 0..4 {
@@ -1,27 +1,47 @@
 /// address appearances
 pub mod address_appearances;
+/// address transactions
+pub mod address_transactions;
 /// balance diffs
 pub mod balance_diffs;
 /// balances
 pub mod balances;
+/// block rewards
+pub mod block_rewards;
 /// blocks
 pub mod blocks;
+/// bundle simulations
+pub mod bundle_simulations;
+/// chainlink rounds
+pub mod chainlink_rounds;
 /// code diffs
 pub mod code_diffs;
 /// codes
 pub mod codes;
 /// contracts
 pub mod contracts;
+/// dex liquidity
+pub mod dex_liquidity;
+/// dex swaps
+pub mod dex_swaps;
+/// ens names
+pub mod ens_names;
 /// erc20 balances
 pub mod erc20_balances;
+/// erc20 holders
+pub mod erc20_holders;
 /// erc20 metadata
 pub mod erc20_metadata;
 /// erc20 supplies
 pub mod erc20_supplies;
 /// erc20 transfers
 pub mod erc20_transfers;
+/// erc4626 vaults
+pub mod erc4626_vaults;
 /// erc721 metadata
 pub mod erc721_metadata;
+/// erc721 owners
+pub mod erc721_owners;
 /// erc721 transfers
 pub mod erc721_transfers;
 /// eth calls
@@ -30,10 +50,18 @@ pub mod eth_calls;
 pub mod geth_balance_diffs;
 /// geth code diffs
 pub mod geth_code_diffs;
+/// geth custom traces
+pub mod geth_custom_traces;
 /// geth nonce diffs
 pub mod geth_nonce_diffs;
+/// geth opcode traces
+pub mod geth_opcode_traces;
+/// geth prestate
+pub mod geth_prestate;
 /// geth storage diffs
 pub mod geth_storage_diffs;
+/// geth trace logs
+pub mod geth_trace_logs;
 /// geth traces
 pub mod geth_traces;
 /// logs
@@ -44,8 +72,12 @@ pub mod native_transfers;
 pub mod nonce_diffs;
 /// nonces
 pub mod nonces;
+/// pending transactions
+pub mod pending_transactions;
 /// prices
 pub mod prices;
+/// proxies
+pub mod proxies;
 /// slots
 pub mod slots;
 /// storage diffs
@@ -56,36 +88,55 @@ pub mod trace_calls;
 pub mod traces;
 /// transactions
 pub mod transactions;
+/// v3 pool states
+pub mod v3_pool_states;
 /// vm traces
 pub mod vm_traces;
 
 pub use address_appearances::*;
+pub use address_transactions::*;
 pub use balance_diffs::*;
 pub use balances::*;
+pub use block_rewards::*;
 pub use blocks::*;
+pub use bundle_simulations::*;
+pub use chainlink_rounds::*;
 pub use code_diffs::*;
 pub use codes::*;
 pub use contracts::*;
+pub use dex_liquidity::*;
+pub use dex_swaps::*;
+pub use ens_names::*;
 pub use erc20_balances::*;
+pub use erc20_holders::*;
 pub use erc20_metadata::*;
 pub use erc20_supplies::*;
 pub use erc20_transfers::*;
+pub use erc4626_vaults::*;
 pub use erc721_metadata::*;
+pub use erc721_owners::*;
 pub use erc721_transfers::*;
 pub use eth_calls::*;
 pub use geth_balance_diffs::*;
 pub use geth_code_diffs::*;
+pub use geth_custom_traces::*;
 pub use geth_nonce_diffs::*;
+pub use geth_opcode_traces::*;
+pub use geth_prestate::*;
 pub use geth_storage_diffs::*;
+pub use geth_trace_logs::*;
 pub use geth_traces::*;
 pub use logs::*;
 pub use native_transfers::*;
 pub use nonce_diffs::*;
 pub use nonces::*;
+pub use pending_transactions::*;
 pub use prices::*;
+pub use proxies::*;
 pub use slots::*;
 pub use storage_diffs::*;
 pub use trace_calls::*;
 pub use traces::*;
 pub use transactions::*;
+pub use v3_pool_states::*;
 pub use vm_traces::*;
@@ -18,14 +18,26 @@ pub struct NativeTransfers {
 }
 
 #[async_trait::async_trait]
-impl Dataset for NativeTransfers {}
+impl Dataset for NativeTransfers {
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::FromAddress, Dim::ToAddress]
+    }
+
+    fn block_range_parameters() -> Vec<Dim> {
+        vec![Dim::FromAddress, Dim::ToAddress]
+    }
+}
 
 #[async_trait::async_trait]
 impl CollectByBlock for NativeTransfers {
     type Response = Vec<Trace>;
 
     async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
-        source.fetcher.trace_block(request.block_number()?.into()).await
+        if request.block_range.is_some() {
+            source.fetcher.trace_filter(request.ethers_trace_filter()?).await
+        } else {
+            source.fetcher.trace_block(request.block_number()?.into()).await
+        }
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
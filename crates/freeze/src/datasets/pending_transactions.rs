@@ -0,0 +1,106 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+
+/// columns for pending transactions
+#[cryo_to_df::to_df(Datatype::PendingTransactions)]
+#[derive(Default)]
+pub struct PendingTransactions {
+    n_rows: u64,
+    first_seen_timestamp_ms: Vec<u64>,
+    transaction_hash: Vec<Vec<u8>>,
+    nonce: Vec<Option<u64>>,
+    from_address: Vec<Option<Vec<u8>>>,
+    to_address: Vec<Option<Vec<u8>>>,
+    value: Vec<Option<U256>>,
+    input: Vec<Option<Vec<u8>>>,
+    gas_limit: Vec<Option<u64>>,
+    gas_price: Vec<Option<u64>>,
+    transaction_type: Vec<Option<u32>>,
+    max_priority_fee_per_gas: Vec<Option<u64>>,
+    max_fee_per_gas: Vec<Option<u64>>,
+    included_block_number: Vec<Option<u32>>,
+    included_transaction_index: Vec<Option<u32>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for PendingTransactions {
+    fn aliases() -> Vec<&'static str> {
+        vec!["mempool"]
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["first_seen_timestamp_ms", "transaction_hash"])
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("latest".to_string())
+    }
+}
+
+/// pending transactions are only collected by streaming, see `cryo_freeze::stream`
+#[async_trait::async_trait]
+impl CollectByBlock for PendingTransactions {
+    type Response = ();
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for PendingTransactions {
+    type Response = ();
+}
+
+/// a transaction observed in the mempool
+#[derive(Default)]
+pub(crate) struct PendingTransaction {
+    /// unix time in milliseconds when the transaction hash was first received
+    pub(crate) first_seen_timestamp_ms: u64,
+    /// transaction body, if it could be fetched before leaving the mempool
+    pub(crate) transaction: Option<Transaction>,
+    /// block number and transaction index where the transaction was included
+    pub(crate) inclusion: Option<(u32, u32)>,
+}
+
+pub(crate) fn process_pending_transaction(
+    tx_hash: H256,
+    pending: PendingTransaction,
+    columns: &mut PendingTransactions,
+    schema: &Table,
+) {
+    let tx = pending.transaction;
+    columns.n_rows += 1;
+    store!(schema, columns, first_seen_timestamp_ms, pending.first_seen_timestamp_ms);
+    store!(schema, columns, transaction_hash, tx_hash.as_bytes().to_vec());
+    store!(schema, columns, nonce, tx.as_ref().map(|tx| tx.nonce.as_u64()));
+    store!(schema, columns, from_address, tx.as_ref().map(|tx| tx.from.as_bytes().to_vec()));
+    store!(
+        schema,
+        columns,
+        to_address,
+        tx.as_ref().and_then(|tx| tx.to.map(|x| x.as_bytes().to_vec()))
+    );
+    store!(schema, columns, value, tx.as_ref().map(|tx| tx.value));
+    store!(schema, columns, input, tx.as_ref().map(|tx| tx.input.to_vec()));
+    store!(schema, columns, gas_limit, tx.as_ref().map(|tx| tx.gas.as_u64()));
+    store!(schema, columns, gas_price, tx.as_ref().and_then(|tx| tx.gas_price.map(|x| x.as_u64())));
+    store!(
+        schema,
+        columns,
+        transaction_type,
+        tx.as_ref().and_then(|tx| tx.transaction_type.map(|x| x.as_u32()))
+    );
+    store!(
+        schema,
+        columns,
+        max_priority_fee_per_gas,
+        tx.as_ref().and_then(|tx| tx.max_priority_fee_per_gas.map(|x| x.as_u64()))
+    );
+    store!(
+        schema,
+        columns,
+        max_fee_per_gas,
+        tx.as_ref().and_then(|tx| tx.max_fee_per_gas.map(|x| x.as_u64()))
+    );
+    store!(schema, columns, included_block_number, pending.inclusion.map(|(block, _)| block));
+    store!(schema, columns, included_transaction_index, pending.inclusion.map(|(_, index)| index));
+}
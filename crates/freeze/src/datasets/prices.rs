@@ -1,38 +1,30 @@
 use crate::*;
-use ethers::abi::{decode, ParamType};
-use ethers::prelude::*;
-use ethers::utils::hex;
-use ethers_core::utils::format_ether;
+use ethers::{
+    abi::{decode, encode, ParamType, Token},
+    prelude::*,
+};
 use polars::prelude::*;
-
-// https://github.com/libevm/eth_call_abuser
-const PRICE_QUERY_CALLDATA: &str = "60a060405262ef902043116100285773d340b57aacdd10f96fc1cf10e15921936f41e29c61003e565b73109830a1aaad605bbf02a9dfa7b0b92ec2fb7daa5b73ffffffffffffffffffffffffffffffffffffffff1660809073ffffffffffffffffffffffffffffffffffffffff1681525034801561007c57600080fd5b5060006100b77388e6a0c2ddd26feeb64f039a2c41296fcb3f564073c02aaa39b223fe8d0a0e5c4f27ead9083c756cc261041b60201b60201c565b905060006100f373c63b0708e2f7e69cb8a1df0e1389a98c35a76d5273a0b86991c6218b36c1d19d4a2e9eb0ce3606eb4861041b60201b60201c565b90506000670de0b6b3a7640000828461010c9190610ccf565b6101169190610d40565b905060006001905061014173a1f8a6807c402e4a15ef4eba36528a3fed24e5776107c060201b60201c565b1561029757678ac7230489e80000735e8422345238f34275888049021821e8e08caa1f73ffffffffffffffffffffffffffffffffffffffff166370a0823173a1f8a6807c402e4a15ef4eba36528a3fed24e5776040518263ffffffff1660e01b81526004016101b09190610db2565b602060405180830381865afa1580156101cd573d6000803e3d6000fd5b505050506040513d601f19601f820116820180604052508101906101f19190610dfe565b11156102965773a1f8a6807c402e4a15ef4eba36528a3fed24e57773ffffffffffffffffffffffffffffffffffffffff16635e0d443f60006001670de0b6b3a76400006040518463ffffffff1660e01b815260040161025293929190610ef3565b602060405180830381865afa15801561026f573d6000803e3d6000fd5b505050506040513d601f19601f820116820180604052508101906102939190610dfe565b90505b5b6000846102d2734e68ccd3e89f51c3074ca5072bbac773960dfa3673c02aaa39b223fe8d0a0e5c4f27ead9083c756cc261041b60201b60201c565b61030a7360594a405d53811d3bc4766596efd80fd545a27073c02aaa39b223fe8d0a0e5c4f27ead9083c756cc261041b60201b60201c565b61034273cbcdf9626bc03e24f779434178a73a0b4bad62ed73c02aaa39b223fe8d0a0e5c4f27ead9083c756cc261041b60201b60201c565b610383733fd4cf9303c4bc9e13772618828712c8eac7dd2f73c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2670de0b6b3a76400006107d360201b60201c565b87876103ab60805173c02aaa39b223fe8d0a0e5c4f27ead9083c756cc261041b60201b60201c565b6103e373a4e0faa58465a2d369aa21b3e42d43374c6f961373c02aaa39b223fe8d0a0e5c4f27ead9083c756cc261041b60201b60201c565b6040516020016103fb99989796959493929190610f39565b604051602081830303815290604052905060008190506020810180590381f35b600061042c836107c060201b60201c565b61043957600190506107ba565b600083905060008173ffffffffffffffffffffffffffffffffffffffff16633850c7bd6040518163ffffffff1660e01b815260040160e060405180830381865afa15801561048b573d6000803e3d6000fd5b505050506040513d601f19601f820116820180604052508101906104af91906110d6565b50505050505090506401000276a473ffffffffffffffffffffffffffffffffffffffff168173ffffffffffffffffffffffffffffffffffffffff16111580610537575073fffd8963efd1fc6a506488495d951d5263988d2573ffffffffffffffffffffffffffffffffffffffff168173ffffffffffffffffffffffffffffffffffffffff1610155b15610547576001925050506107ba565b60008273ffffffffffffffffffffffffffffffffffffffff16630dfe16816040518163ffffffff1660e01b8152600401602060405180830381865afa158015610594573d6000803e3d6000fd5b505050506040513d601f19601f820116820180604052508101906105b891906111a4565b905060008373ffffffffffffffffffffffffffffffffffffffff1663d21220a76040518163ffffffff1660e01b8152600401602060405180830381865afa158015610607573d6000803e3d6000fd5b505050506040513d601f19601f8201168201806040525081019061062b91906111a4565b905060008273ffffffffffffffffffffffffffffffffffffffff1663313ce5676040518163ffffffff1660e01b8152600401602060405180830381865afa15801561067a573d6000803e3d6000fd5b505050506040513d601f19601f8201168201806040525081019061069e9190610dfe565b905060008273ffffffffffffffffffffffffffffffffffffffff1663313ce5676040518163ffffffff1660e01b8152600401602060405180830381865afa1580156106ed573d6000803e3d6000fd5b505050506040513d601f19601f820116820180604052508101906107119190610dfe565b905060006107258684610b3b60201b60201c565b90508873ffffffffffffffffffffffffffffffffffffffff168573ffffffffffffffffffffffffffffffffffffffff160361078c5781601261076791906111d1565b600a6107739190611338565b8161077e9190610ccf565b9750505050505050506107ba565b8082601261079a9190611383565b600a6107a69190611338565b6107b09190610d40565b9750505050505050505b92915050565b600080823b905060008111915050919050565b60006107e4846107c060201b60201c565b6107f15760019050610b34565b600084905060008173ffffffffffffffffffffffffffffffffffffffff16630dfe16816040518163ffffffff1660e01b8152600401602060405180830381865afa158015610843573d6000803e3d6000fd5b505050506040513d601f19601f8201168201806040525081019061086791906111a4565b905060008273ffffffffffffffffffffffffffffffffffffffff1663d21220a76040518163ffffffff1660e01b8152600401602060405180830381865afa1580156108b6573d6000803e3d6000fd5b505050506040513d601f19601f820116820180604052508101906108da91906111a4565b90506000808473ffffffffffffffffffffffffffffffffffffffff16630902f1ac6040518163ffffffff1660e01b8152600401606060405180830381865afa15801561092a573d6000803e3d6000fd5b505050506040513d601f19601f8201168201806040525081019061094e9190611439565b506dffffffffffffffffffffffffffff1691506dffffffffffffffffffffffffffff16915060008473ffffffffffffffffffffffffffffffffffffffff1663313ce5676040518163ffffffff1660e01b8152600401602060405180830381865afa1580156109c0573d6000803e3d6000fd5b505050506040513d601f19601f820116820180604052508101906109e49190610dfe565b905060008473ffffffffffffffffffffffffffffffffffffffff1663313ce5676040518163ffffffff1660e01b8152600401602060405180830381865afa158015610a33573d6000803e3d6000fd5b505050506040513d601f19601f82011682018060405250810190610a579190610dfe565b90508473ffffffffffffffffffffffffffffffffffffffff168a73ffffffffffffffffffffffffffffffffffffffff1603610ae25780600a610a999190611338565b84610aa49190610ccf565b935081600a610ab39190611338565b83858b610ac09190610ccf565b610aca9190610d40565b610ad49190610d40565b975050505050505050610b34565b81600a610aef9190611338565b83610afa9190610ccf565b925080600a610b099190611338565b84848b610b169190610ccf565b610b209190610d40565b610b2a9190610d40565b9750505050505050505b9392505050565b6000808373ffffffffffffffffffffffffffffffffffffffff168473ffffffffffffffffffffffffffffffffffffffff16610b769190610ccf565b9050600083600a610b87919061148c565b9050610bb382827801000000000000000000000000000000000000000000000000610bbd60201b60201c565b9250505092915050565b6000806000801985870985870292508281108382030391505060008103610bf75760008411610beb57600080fd5b83820492505050610c8f565b808411610c0357600080fd5b600084868809905082811182039150808303925060008586600003169050808604955080840493506001818260000304019050808302841793506000600287600302189050808702600203810290508087026002038102905080870260020381029050808702600203810290508087026002038102905080870260020381029050808502955050505050505b9392505050565b6000819050919050565b7f4e487b7100000000000000000000000000000000000000000000000000000000600052601160045260246000fd5b6000610cda82610c96565b9150610ce583610c96565b9250828202610cf381610c96565b91508282048414831517610d0a57610d09610ca0565b5b5092915050565b7f4e487b7100000000000000000000000000000000000000000000000000000000600052601260045260246000fd5b6000610d4b82610c96565b9150610d5683610c96565b925082610d6657610d65610d11565b5b828204905092915050565b600073ffffffffffffffffffffffffffffffffffffffff82169050919050565b6000610d9c82610d71565b9050919050565b610dac81610d91565b82525050565b6000602082019050610dc76000830184610da3565b92915050565b600080fd5b610ddb81610c96565b8114610de657600080fd5b50565b600081519050610df881610dd2565b92915050565b600060208284031215610e1457610e13610dcd565b5b6000610e2284828501610de9565b91505092915050565b6000819050919050565b600081600f0b9050919050565b6000819050919050565b6000610e67610e62610e5d84610e2b565b610e42565b610e35565b9050919050565b610e7781610e4c565b82525050565b6000819050919050565b6000610ea2610e9d610e9884610e7d565b610e42565b610e35565b9050919050565b610eb281610e87565b82525050565b6000819050919050565b6000610edd610ed8610ed384610eb8565b610e42565b610c96565b9050919050565b610eed81610ec2565b82525050565b6000606082019050610f086000830186610e6e565b610f156020830185610ea9565b610f226040830184610ee4565b949350505050565b610f3381610c96565b82525050565b600061012082019050610f4f600083018c610f2a565b610f5c602083018b610f2a565b610f69604083018a610f2a565b610f766060830189610f2a565b610f836080830188610f2a565b610f9060a0830187610f2a565b610f9d60c0830186610f2a565b610faa60e0830185610f2a565b610fb8610100830184610f2a565b9a9950505050505050505050565b610fcf81610d71565b8114610fda57600080fd5b50565b600081519050610fec81610fc6565b92915050565b60008160020b9050919050565b61100881610ff2565b811461101357600080fd5b50565b60008151905061102581610fff565b92915050565b600061ffff82169050919050565b6110428161102b565b811461104d57600080fd5b50565b60008151905061105f81611039565b92915050565b600060ff82169050919050565b61107b81611065565b811461108657600080fd5b50565b60008151905061109881611072565b92915050565b60008115159050919050565b6110b38161109e565b81146110be57600080fd5b50565b6000815190506110d0816110aa565b92915050565b600080600080600080600060e0888a0312156110f5576110f4610dcd565b5b60006111038a828b01610fdd565b97505060206111148a828b01611016565b96505060406111258a828b01611050565b95505060606111368a828b01611050565b94505060806111478a828b01611050565b93505060a06111588a828b01611089565b92505060c06111698a828b016110c1565b91505092959891949750929550565b61118181610d91565b811461118c57600080fd5b50565b60008151905061119e81611178565b92915050565b6000602082840312156111ba576111b9610dcd565b5b60006111c88482850161118f565b91505092915050565b60006111dc82610c96565b91506111e783610c96565b92508282039050818111156111ff576111fe610ca0565b5b92915050565b60008160011c9050919050565b6000808291508390505b600185111561125c5780860481111561123857611237610ca0565b5b60018516156112475780820291505b808102905061125585611205565b945061121c565b94509492505050565b6000826112755760019050611331565b816112835760009050611331565b816001811461129957600281146112a3576112d2565b6001915050611331565b60ff8411156112b5576112b4610ca0565b5b8360020a9150848211156112cc576112cb610ca0565b5b50611331565b5060208310610133831016604e8410600b84101617156113075782820a90508381111561130257611301610ca0565b5b611331565b6113148484846001611212565b9250905081840481111561132b5761132a610ca0565b5b81810290505b9392505050565b600061134382610c96565b915061134e83610c96565b925061137b7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff8484611265565b905092915050565b600061138e82610c96565b915061139983610c96565b92508282019050808211156113b1576113b0610ca0565b5b92915050565b60006dffffffffffffffffffffffffffff82169050919050565b6113da816113b7565b81146113e557600080fd5b50565b6000815190506113f7816113d1565b92915050565b600063ffffffff82169050919050565b611416816113fd565b811461142157600080fd5b50565b6000815190506114338161140d565b92915050565b60008060006060848603121561145257611451610dcd565b5b6000611460868287016113e8565b9350506020611471868287016113e8565b925050604061148286828701611424565b9150509250925092565b600061149782610c96565b91506114a283611065565b92506114cf7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff8484611265565b90509291505056fe";
+use serde::Deserialize;
+use std::{collections::HashMap, sync::Mutex};
 
 /// Columns for prices
 #[cryo_to_df::to_df(Datatype::Prices)]
 #[derive(Default)]
 pub struct Prices {
     n_rows: usize,
-
     block_number: Vec<u32>,
-
-    weth_usdc: Vec<f64>,
-    weth_usdt: Vec<f64>,
-    weth_dai: Vec<f64>,
-    weth_wbtc: Vec<f64>,
-    weth_bnt: Vec<f64>,
-    weth_frax: Vec<f64>,
-    weth_frxeth: Vec<f64>,
-    weth_wsteth: Vec<f64>,
-    weth_reth: Vec<f64>,
-
+    base: Vec<String>,
+    quote: Vec<String>,
+    source: Vec<String>,
+    pool: Vec<Vec<u8>>,
+    price: Vec<Option<f64>>,
     chain_id: Vec<u64>,
 }
 
 #[async_trait::async_trait]
 impl Dataset for Prices {
     fn default_sort() -> Option<Vec<&'static str>> {
-        Some(vec!["block_number"])
+        Some(vec!["block_number", "base", "quote", "source", "pool"])
     }
 
     fn default_blocks() -> Option<String> {
@@ -40,50 +32,158 @@ impl Dataset for Prices {
     }
 }
 
-type Result<T> = ::core::result::Result<T, CollectError>;
-type BlockPrices = (u32, Vec<f64>);
+/// Protocol used to read a price
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceSourceKind {
+    /// uniswap v2 style pair, priced from getReserves()
+    UniswapV2,
+    /// uniswap v3 style pool, priced from slot0()
+    UniswapV3,
+    /// chainlink aggregator, priced from latestRoundData()
+    Chainlink,
+    /// curve pool, priced from get_dy()
+    Curve,
+}
+
+impl PriceSourceKind {
+    /// name of price source kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceSourceKind::UniswapV2 => "uniswap_v2",
+            PriceSourceKind::UniswapV3 => "uniswap_v3",
+            PriceSourceKind::Chainlink => "chainlink",
+            PriceSourceKind::Curve => "curve",
+        }
+    }
+}
+
+/// Pool or oracle that prices a base asset in units of a quote asset
+#[derive(Clone, Debug, Deserialize)]
+pub struct PriceSource {
+    /// protocol of pool or oracle
+    pub source: PriceSourceKind,
+    /// address of pool or oracle
+    pub address: H160,
+    /// symbol of base asset
+    pub base: String,
+    /// symbol of quote asset
+    pub quote: String,
+    /// decimals of base asset [default: 18]
+    pub base_decimals: Option<u32>,
+    /// decimals of quote asset, for chainlink the decimals of the answer [default: 18, or
+    /// decimals() of the chainlink feed]
+    pub quote_decimals: Option<u32>,
+    /// whether the base asset is token0 of a uniswap pool
+    #[serde(default = "default_base_is_token0")]
+    pub base_is_token0: bool,
+    /// index of base asset in a curve pool
+    #[serde(default)]
+    pub base_index: i32,
+    /// index of quote asset in a curve pool
+    #[serde(default = "default_quote_index")]
+    pub quote_index: i32,
+}
+
+fn default_base_is_token0() -> bool {
+    true
+}
+
+fn default_quote_index() -> i32 {
+    1
+}
+
+// well-known ethereum pools and oracles, used when no price sources are given
+const MAINNET_PRICE_SOURCES: &str = r#"[
+    {"source": "uniswap_v3", "address": "0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640", "base": "WETH", "quote": "USDC", "base_decimals": 18, "quote_decimals": 6, "base_is_token0": false},
+    {"source": "uniswap_v3", "address": "0x4e68ccd3e89f51c3074ca5072bbac773960dfa36", "base": "WETH", "quote": "USDT", "base_decimals": 18, "quote_decimals": 6, "base_is_token0": true},
+    {"source": "uniswap_v3", "address": "0x60594a405d53811d3bc4766596efd80fd545a270", "base": "WETH", "quote": "DAI", "base_decimals": 18, "quote_decimals": 18, "base_is_token0": false},
+    {"source": "uniswap_v3", "address": "0xcbcdf9626bc03e24f779434178a73a0b4bad62ed", "base": "WETH", "quote": "WBTC", "base_decimals": 18, "quote_decimals": 8, "base_is_token0": false},
+    {"source": "uniswap_v2", "address": "0xb4e16d0168e52d35cacd2c6185b44281ec28c9dc", "base": "WETH", "quote": "USDC", "base_decimals": 18, "quote_decimals": 6, "base_is_token0": false},
+    {"source": "chainlink", "address": "0x5f4ec3df9cbd43714fe2740f5e3616155c5b8419", "base": "ETH", "quote": "USD", "quote_decimals": 8},
+    {"source": "curve", "address": "0xdc24316b9ae028f1497c275eb9192a3ea0f67022", "base": "stETH", "quote": "ETH", "base_index": 1, "quote_index": 0}
+]"#;
+
+lazy_static::lazy_static! {
+    static ref DEFAULT_MAINNET_PRICE_SOURCES: Vec<PriceSource> =
+        PriceSource::parse(MAINNET_PRICE_SOURCES).expect("invalid default price sources");
+
+    /// decimals() of chainlink feeds, keyed by chain id and feed address
+    static ref FEED_DECIMALS: Mutex<HashMap<(u64, H160), u32>> = Mutex::new(HashMap::new());
+}
+
+impl PriceSource {
+    /// load price sources from a json file containing a list of price sources
+    pub fn load_file(path: &str) -> Result<Vec<PriceSource>, ParseError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|_| ParseError::ParseError(format!("could not read {}", path)))?;
+        Self::parse(&contents)
+    }
+
+    /// parse price sources from a json list of price sources
+    pub fn parse(contents: &str) -> Result<Vec<PriceSource>, ParseError> {
+        let price_sources: Vec<PriceSource> = serde_json::from_str(contents)
+            .map_err(|e| ParseError::ParseError(format!("invalid price sources: {}", e)))?;
+        for price_source in price_sources.iter() {
+            // amounts of one whole token are computed as 10^decimals, which must fit a U256
+            let decimals = [price_source.base_decimals, price_source.quote_decimals];
+            if decimals.iter().flatten().any(|decimals| *decimals > 77) {
+                return Err(ParseError::ParseError(format!(
+                    "decimals of price source {:?} must be at most 77",
+                    price_source.address
+                )))
+            }
+        }
+        Ok(price_sources)
+    }
+
+    /// default price sources for a chain
+    pub fn chain_defaults(chain_id: u64) -> Option<Vec<PriceSource>> {
+        match chain_id {
+            1 => Some(DEFAULT_MAINNET_PRICE_SOURCES.clone()),
+            _ => None,
+        }
+    }
+}
+
+type BlockPrices = (u32, Vec<(PriceSource, Option<f64>)>);
 
 #[async_trait::async_trait]
 impl CollectByBlock for Prices {
     type Response = BlockPrices;
 
-    async fn extract(
-        request: Params,
-        source: Arc<Source>,
-        _: Arc<Query>,
-    ) -> Result<Self::Response> {
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
         let block_number = request.block_number()? as u32;
+        let price_sources = match &query.price_sources {
+            Some(price_sources) => price_sources.clone(),
+            None => PriceSource::chain_defaults(source.chain_id)
+                .ok_or(err("must specify --price-sources for prices on this chain"))?,
+        };
 
-        let call_data: Vec<u8> = hex::decode(PRICE_QUERY_CALLDATA).unwrap();
-        let transaction =
-            TransactionRequest { data: Some(call_data.clone().into()), ..Default::default() };
-
-        let output = source.fetcher.call(transaction, block_number.into()).await?;
-        let resp: Vec<f64> = decode(
-            &[
-                ParamType::Uint(256),
-                ParamType::Uint(256),
-                ParamType::Uint(256),
-                ParamType::Uint(256),
-                ParamType::Uint(256),
-                ParamType::Uint(256),
-                ParamType::Uint(256),
-                ParamType::Uint(256),
-                ParamType::Uint(256),
-            ],
-            &output,
-        )
-        .unwrap()
-        .iter()
-        .map(|x| format_ether(x.clone().into_uint().unwrap()).parse::<f64>().unwrap())
-        .collect();
-
-        Ok((block_number, resp))
-    }
-
-    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> Result<()> {
+        let mut futures = Vec::new();
+        for price_source in price_sources.into_iter() {
+            let source = source.clone();
+            futures.push(async move {
+                let price = get_price(&price_source, source, block_number.into()).await;
+                (price_source, price)
+            });
+        }
+        Ok((block_number, futures::future::join_all(futures).await))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let schema = query.schemas.get_schema(&Datatype::Prices)?;
-        process_prices(columns, response, schema)
+        let (block, prices) = response;
+        for (price_source, price) in prices.into_iter() {
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, block);
+            store!(schema, columns, base, price_source.base);
+            store!(schema, columns, quote, price_source.quote);
+            store!(schema, columns, source, price_source.source.as_str().to_string());
+            store!(schema, columns, pool, price_source.address.as_bytes().to_vec());
+            store!(schema, columns, price, price);
+        }
+        Ok(())
     }
 }
 
@@ -92,19 +192,210 @@ impl CollectByTransaction for Prices {
     type Response = ();
 }
 
-fn process_prices(columns: &mut Prices, data: BlockPrices, schema: &Table) -> Result<()> {
-    let (block, prices) = data;
-    columns.n_rows += 1;
-    store!(schema, columns, block_number, block);
-    store!(schema, columns, weth_usdc, prices[0]);
-    store!(schema, columns, weth_usdt, prices[1]);
-    store!(schema, columns, weth_dai, prices[2]);
-    store!(schema, columns, weth_wbtc, prices[3]);
-    store!(schema, columns, weth_bnt, prices[4]);
-    store!(schema, columns, weth_frax, prices[5]);
-    store!(schema, columns, weth_frxeth, prices[6]);
-    store!(schema, columns, weth_wsteth, prices[7]);
-    store!(schema, columns, weth_reth, prices[8]);
-
-    Ok(())
+/// read price of a single source, failed calls and malformed outputs give None
+async fn get_price(
+    price_source: &PriceSource,
+    source: Arc<Source>,
+    block_number: BlockNumber,
+) -> Option<f64> {
+    let call = |call_data| source.fetcher.call2(price_source.address, call_data, block_number);
+    let price = match price_source.source {
+        PriceSourceKind::UniswapV2 => {
+            let output = call(FUNCTION_UNISWAP_V2_GET_RESERVES.clone()).await.ok()?;
+            price_source.uniswap_v2_price(&output)?
+        }
+        PriceSourceKind::UniswapV3 => {
+            let output = call(FUNCTION_UNISWAP_V3_SLOT0.clone()).await.ok()?;
+            price_source.uniswap_v3_price(&output)?
+        }
+        PriceSourceKind::Chainlink => {
+            let quote_decimals = match price_source.quote_decimals {
+                Some(decimals) => decimals,
+                None => chainlink_decimals(price_source.address, &source, block_number).await?,
+            };
+            let output = call(FUNCTION_CHAINLINK_LATEST_ROUND_DATA.clone()).await.ok()?;
+            chainlink_price(&output, quote_decimals)?
+        }
+        PriceSourceKind::Curve => {
+            let output = call(price_source.curve_call_data()).await.ok()?;
+            price_source.curve_price(&output)?
+        }
+    };
+    if price.is_finite() && price > 0.0 {
+        Some(price)
+    } else {
+        None
+    }
+}
+
+impl PriceSource {
+    fn decimals_scale(&self) -> f64 {
+        let base_decimals = self.base_decimals.unwrap_or(18) as i32;
+        let quote_decimals = self.quote_decimals.unwrap_or(18) as i32;
+        10f64.powi(base_decimals - quote_decimals)
+    }
+
+    /// price from getReserves() output
+    fn uniswap_v2_price(&self, output: &[u8]) -> Option<f64> {
+        let reserves = decode_uints(&[112, 112, 32], output)?;
+        let (base_reserve, quote_reserve) = if self.base_is_token0 {
+            (reserves[0], reserves[1])
+        } else {
+            (reserves[1], reserves[0])
+        };
+        Some((quote_reserve / base_reserve) * self.decimals_scale())
+    }
+
+    /// price from slot0() output
+    fn uniswap_v3_price(&self, output: &[u8]) -> Option<f64> {
+        let sqrt_price_x96 = decode_uints(&[160], output.get(0..32)?)?[0];
+        let token1_per_token0 = (sqrt_price_x96 / 2f64.powi(96)).powi(2);
+        let raw_price =
+            if self.base_is_token0 { token1_per_token0 } else { 1.0 / token1_per_token0 };
+        Some(raw_price * self.decimals_scale())
+    }
+
+    /// get_dy() call swapping one whole base token
+    fn curve_call_data(&self) -> Vec<u8> {
+        let amount_in = U256::exp10(self.base_decimals.unwrap_or(18) as usize);
+        let mut call_data = FUNCTION_CURVE_GET_DY.clone();
+        call_data.extend(encode(&[
+            Token::Int(I256::from(self.base_index).into_raw()),
+            Token::Int(I256::from(self.quote_index).into_raw()),
+            Token::Uint(amount_in),
+        ]));
+        call_data
+    }
+
+    /// price from get_dy() output
+    fn curve_price(&self, output: &[u8]) -> Option<f64> {
+        let amount_out = decode_uints(&[256], output)?[0];
+        Some(amount_out / 10f64.powi(self.quote_decimals.unwrap_or(18) as i32))
+    }
+}
+
+/// decimals() of a chainlink feed, cached per feed once fetched
+async fn chainlink_decimals(
+    address: H160,
+    source: &Source,
+    block_number: BlockNumber,
+) -> Option<u32> {
+    let key = (source.chain_id, address);
+    if let Some(decimals) = feed_decimals().get(&key) {
+        return Some(*decimals)
+    }
+    let output =
+        source.fetcher.call2(address, FUNCTION_ERC20_DECIMALS.clone(), block_number).await.ok()?;
+    let decimals = bytes_to_u32(output).ok()?;
+    feed_decimals().insert(key, decimals);
+    Some(decimals)
+}
+
+fn feed_decimals() -> std::sync::MutexGuard<'static, HashMap<(u64, H160), u32>> {
+    match FEED_DECIMALS.lock() {
+        Ok(cache) => cache,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// price from latestRoundData() output
+fn chainlink_price(output: &[u8], quote_decimals: u32) -> Option<f64> {
+    let answer = match decode(&[ParamType::Uint(80), ParamType::Int(256)], output).ok()? {
+        tokens if tokens.len() == 2 => tokens[1].clone().into_int()?,
+        _ => return None,
+    };
+    let answer = I256::from_raw(answer).to_string().parse::<f64>().ok()?;
+    Some(answer / 10f64.powi(quote_decimals as i32))
+}
+
+/// decode unsigned integers of given bit sizes as f64
+fn decode_uints(sizes: &[usize], data: &[u8]) -> Option<Vec<f64>> {
+    let param_types: Vec<ParamType> = sizes.iter().map(|size| ParamType::Uint(*size)).collect();
+    let tokens = decode(&param_types, data).ok()?;
+    tokens
+        .into_iter()
+        .map(|token| token.into_uint().and_then(|x| x.to_string().parse::<f64>().ok()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mainnet_price_sources() {
+        let sources = PriceSource::chain_defaults(1).expect("no mainnet defaults");
+        assert_eq!(sources.len(), 7);
+        assert!(sources.iter().any(|x| x.source == PriceSourceKind::Curve && x.base_index == 1));
+        assert!(PriceSource::chain_defaults(10).is_none());
+    }
+
+    fn price_source(json: &str) -> PriceSource {
+        PriceSource::parse(&format!("[{}]", json)).unwrap().remove(0)
+    }
+
+    fn assert_close(price: Option<f64>, expected: f64) {
+        assert!((price.expect("no price") - expected).abs() < expected * 1e-12);
+    }
+
+    fn words(values: &[U256]) -> Vec<u8> {
+        encode(&values.iter().map(|x| Token::Uint(*x)).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_parse_price_sources() {
+        let json = |decimals| {
+            format!(
+                r#"[{{"source": "curve", "address": "0x0000000000000000000000000000000000000001", "base": "A", "quote": "B", "base_decimals": {}}}]"#,
+                decimals
+            )
+        };
+        assert!(PriceSource::parse(&json(77)).is_ok());
+        assert!(PriceSource::parse(&json(78)).is_err());
+    }
+
+    #[test]
+    fn test_decode_uints() {
+        let output = words(&[U256::from(3), U256::exp10(20), U256::from(1)]);
+        assert_eq!(decode_uints(&[112, 112, 32], &output), Some(vec![3.0, 1e20, 1.0]));
+        assert_eq!(decode_uints(&[256], &output[..31]), None);
+    }
+
+    #[test]
+    fn test_uniswap_prices() {
+        // 1 WETH = 2000 USDC, with WETH as token1 of the pool
+        let v2 = price_source(
+            r#"{"source": "uniswap_v2", "address": "0x0000000000000000000000000000000000000001", "base": "WETH", "quote": "USDC", "base_decimals": 18, "quote_decimals": 6, "base_is_token0": false}"#,
+        );
+        let reserves = words(&[U256::from(2_000_000_000u64), U256::exp10(18), U256::zero()]);
+        assert_close(v2.uniswap_v2_price(&reserves), 2000.0);
+
+        // sqrtPriceX96 of 2^96 is a raw price of 1 token1 per token0
+        let v3 = price_source(
+            r#"{"source": "uniswap_v3", "address": "0x0000000000000000000000000000000000000001", "base": "A", "quote": "B", "base_decimals": 8, "quote_decimals": 6}"#,
+        );
+        let slot0 = words(&[U256::from(1) << 96, U256::zero()]);
+        assert_close(v3.uniswap_v3_price(&slot0), 100.0);
+    }
+
+    #[test]
+    fn test_chainlink_and_curve_prices() {
+        let round_data = encode(&[
+            Token::Uint(U256::from(1)),
+            Token::Int(I256::from(200_012_345_678i64).into_raw()),
+            Token::Uint(U256::zero()),
+            Token::Uint(U256::zero()),
+            Token::Uint(U256::from(1)),
+        ]);
+        assert_eq!(chainlink_price(&round_data, 8), Some(2000.12345678));
+
+        let curve = price_source(
+            r#"{"source": "curve", "address": "0x0000000000000000000000000000000000000001", "base": "stETH", "quote": "ETH", "base_index": 1, "quote_index": 0}"#,
+        );
+        let call_data = curve.curve_call_data();
+        assert_eq!(call_data[..4], FUNCTION_CURVE_GET_DY[..]);
+        assert_eq!(U256::from_big_endian(&call_data[68..100]), U256::exp10(18));
+        let amount_out = words(&[U256::exp10(17) * 9]);
+        assert_eq!(curve.curve_price(&amount_out), Some(0.9));
+    }
 }
@@ -0,0 +1,178 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+
+/// columns for proxies
+#[cryo_to_df::to_df(Datatype::Proxies)]
+#[derive(Default)]
+pub struct Proxies {
+    n_rows: usize,
+    block_number: Vec<u32>,
+    address: Vec<Vec<u8>>,
+    proxy_type: Vec<Option<String>>,
+    implementation: Vec<Option<Vec<u8>>>,
+    admin: Vec<Option<Vec<u8>>>,
+    beacon: Vec<Option<Vec<u8>>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for Proxies {
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "address"])
+    }
+
+    fn required_parameters() -> Vec<Dim> {
+        vec![Dim::Address]
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Contract, Dim::Address)].into_iter().collect())
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("latest".to_string())
+    }
+}
+
+/// proxy pattern detected at an address
+#[derive(Default)]
+pub struct ProxyInfo {
+    block_number: u32,
+    address: Vec<u8>,
+    proxy_type: Option<&'static str>,
+    implementation: Option<H160>,
+    admin: Option<H160>,
+    beacon: Option<H160>,
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for Proxies {
+    type Response = ProxyInfo;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let address = request.ethers_address()?;
+        let block_number = request.block_number()?;
+        let block: BlockNumber = block_number.into();
+        let fetcher = &source.fetcher;
+        let (code, implementation, admin, beacon, proxiable, slot0) = futures::join!(
+            fetcher.get_code(address, block),
+            fetcher.get_storage_at(address, *SLOT_EIP1967_IMPLEMENTATION, block),
+            fetcher.get_storage_at(address, *SLOT_EIP1967_ADMIN, block),
+            fetcher.get_storage_at(address, *SLOT_EIP1967_BEACON, block),
+            fetcher.get_storage_at(address, *SLOT_EIP1822_PROXIABLE, block),
+            fetcher.get_storage_at(address, H256::zero(), block),
+        );
+        let code = code?;
+        let admin = slot_to_address(admin?);
+
+        let mut info = ProxyInfo {
+            block_number: block_number as u32,
+            address: request.address()?,
+            admin,
+            ..Default::default()
+        };
+        if let Some(implementation) = minimal_proxy_implementation(&code) {
+            info.proxy_type = Some("eip1167");
+            info.implementation = Some(implementation);
+        } else if let Some(implementation) = slot_to_address(implementation?) {
+            info.proxy_type = Some("eip1967");
+            info.implementation = Some(implementation);
+        } else if let Some(beacon) = slot_to_address(beacon?) {
+            let call_data = FUNCTION_BEACON_IMPLEMENTATION.clone();
+            let output = fetcher.call2(beacon, call_data, block).await.ok();
+            info.proxy_type = Some("eip1967_beacon");
+            info.beacon = Some(beacon);
+            info.implementation = output.and_then(|x| bytes_to_address(&x));
+        } else if let Some(implementation) = slot_to_address(proxiable?) {
+            info.proxy_type = Some("eip1822");
+            info.implementation = Some(implementation);
+        } else if let Some(implementation) = slot_to_address(slot0?) {
+            if is_safe_proxy(&code) {
+                info.proxy_type = Some("gnosis_safe");
+                info.implementation = Some(implementation);
+            }
+        }
+        Ok(info)
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::Proxies)?;
+        let to_bytes = |x: Option<H160>| x.map(|x| x.as_bytes().to_vec());
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, response.block_number);
+        store!(schema, columns, address, response.address);
+        store!(schema, columns, proxy_type, response.proxy_type.map(|x| x.to_string()));
+        store!(schema, columns, implementation, to_bytes(response.implementation));
+        store!(schema, columns, admin, to_bytes(response.admin));
+        store!(schema, columns, beacon, to_bytes(response.beacon));
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for Proxies {
+    type Response = ();
+}
+
+/// interpret a storage slot as an address, empty slots and other values give None
+fn slot_to_address(value: H256) -> Option<H160> {
+    bytes_to_address(value.as_bytes())
+}
+
+const EIP1167_PREFIX: [u8; 10] = [0x36, 0x3d, 0x3d, 0x37, 0x3d, 0x3d, 0x3d, 0x36, 0x3d, 0x73];
+const EIP1167_SUFFIX: [u8; 15] =
+    [0x5a, 0xf4, 0x3d, 0x82, 0x80, 0x3e, 0x90, 0x3d, 0x91, 0x60, 0x2b, 0x57, 0xfd, 0x5b, 0xf3];
+
+/// implementation of an eip1167 minimal proxy, from its bytecode
+fn minimal_proxy_implementation(code: &[u8]) -> Option<H160> {
+    if code.len() == 45 && code[..10] == EIP1167_PREFIX && code[30..] == EIP1167_SUFFIX {
+        Some(H160::from_slice(&code[10..30]))
+    } else {
+        None
+    }
+}
+
+/// whether bytecode is a safe proxy, which answers masterCopy() itself
+///
+/// safe proxies compare calldata against the selector pushed as a left-aligned 32 byte word,
+/// ex: `7f a619486e 00..00`
+fn is_safe_proxy(code: &[u8]) -> bool {
+    let mut push_selector = vec![0x7f];
+    push_selector.extend(FUNCTION_GNOSIS_SAFE_MASTER_COPY.iter());
+    push_selector.extend([0u8; 28]);
+    code.windows(push_selector.len()).any(|x| x == push_selector.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimal_proxy_implementation() {
+        let implementation = H160::from_low_u64_be(0xbeef);
+        let mut code = EIP1167_PREFIX.to_vec();
+        code.extend(implementation.as_bytes());
+        code.extend(EIP1167_SUFFIX);
+        assert_eq!(minimal_proxy_implementation(&code), Some(implementation));
+        assert_eq!(minimal_proxy_implementation(&code[1..]), None);
+    }
+
+    #[test]
+    fn test_is_safe_proxy() {
+        // runtime bytecode of GnosisSafeProxy v1.3.0
+        let code = prefix_hex::decode::<Vec<u8>>(
+            "0x608060405273ffffffffffffffffffffffffffffffffffffffff600054167fa619486e00000000000000\
+             00000000000000000000000000000000000000000060003514156050578060005260206000f35b36600080\
+             37600080366000845af43d6000803e60008114156070573d6000fd5b3d6000f3fea2646970667358221220\
+             d1429297349653a4918076d650332de1a1068c5f3e07c5c82360c277770b955264736f6c63430007060033",
+        )
+        .unwrap();
+        assert!(is_safe_proxy(&code));
+
+        // selector pushed with PUSH4, as in a contract that calls masterCopy()
+        let mut code = vec![0x63];
+        code.extend(FUNCTION_GNOSIS_SAFE_MASTER_COPY.iter());
+        assert!(!is_safe_proxy(&code));
+    }
+}
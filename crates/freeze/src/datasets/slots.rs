@@ -11,6 +11,10 @@ pub struct Slots {
     address: Vec<Vec<u8>>,
     slot: Vec<Vec<u8>>,
     value: Vec<Vec<u8>>,
+    variable: Vec<Option<String>>,
+    mapping_keys: Vec<Option<String>>,
+    value_type: Vec<Option<String>>,
+    decoded_value: Vec<Option<String>>,
     chain_id: Vec<u64>,
 }
 
@@ -20,6 +24,10 @@ impl Dataset for Slots {
         Some(vec!["block_number", "address", "slot"])
     }
 
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "address", "slot", "value", "chain_id"])
+    }
+
     fn required_parameters() -> Vec<Dim> {
         vec![Dim::Address, Dim::Slot]
     }
@@ -71,10 +79,21 @@ impl CollectByTransaction for Slots {
 
 fn process_nonce(columns: &mut Slots, data: BlockTxAddressOutput, schema: &Table) -> R<()> {
     let (block, _tx, address, slot, output) = data;
+    let decoded = match &schema.storage_decoder {
+        Some(decoder) => decoder
+            .slot_index(&H160::from_slice(&address), &[])
+            .map(|index| index.decode(&H256::from_slice(&slot), &[H256::from_slice(&output)]))
+            .unwrap_or_default(),
+        None => DecodedSlot::default(),
+    };
     columns.n_rows += 1;
     store!(schema, columns, block_number, block);
     store!(schema, columns, address, address);
     store!(schema, columns, slot, slot);
     store!(schema, columns, value, output);
+    store!(schema, columns, variable, decoded.variable);
+    store!(schema, columns, mapping_keys, decoded.mapping_keys);
+    store!(schema, columns, value_type, decoded.value_type);
+    store!(schema, columns, decoded_value, decoded.values.into_iter().next().flatten());
     Ok(())
 }
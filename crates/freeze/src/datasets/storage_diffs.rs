@@ -14,11 +14,29 @@ pub struct StorageDiffs {
     pub(crate) slot: Vec<Vec<u8>>,
     pub(crate) from_value: Vec<Vec<u8>>,
     pub(crate) to_value: Vec<Vec<u8>>,
+    pub(crate) variable: Vec<Option<String>>,
+    pub(crate) mapping_keys: Vec<Option<String>>,
+    pub(crate) value_type: Vec<Option<String>>,
+    pub(crate) decoded_from_value: Vec<Option<String>>,
+    pub(crate) decoded_to_value: Vec<Option<String>>,
     pub(crate) chain_id: Vec<u64>,
 }
 
 #[async_trait::async_trait]
-impl Dataset for StorageDiffs {}
+impl Dataset for StorageDiffs {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "transaction_hash",
+            "address",
+            "slot",
+            "from_value",
+            "to_value",
+            "chain_id",
+        ])
+    }
+}
 
 type BlockTxsTraces = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<ethers::types::BlockTrace>);
 
@@ -59,8 +77,10 @@ pub(crate) fn process_storage_diffs(
     let (block_number, txs, traces) = response;
     for (index, (trace, tx)) in traces.iter().zip(txs).enumerate() {
         if let Some(ethers::types::StateDiff(state_diffs)) = &trace.state_diff {
+            let keys: Vec<H256> = state_diffs.keys().map(|addr| H256::from(*addr)).collect();
             for (addr, diff) in state_diffs.iter() {
-                process_storage_diff(addr, &diff.storage, block_number, tx, index, columns, schema);
+                let tx_info = (block_number, tx, index);
+                process_storage_diff(addr, &diff.storage, tx_info, &keys, columns, schema);
             }
         }
     }
@@ -70,12 +90,13 @@ pub(crate) fn process_storage_diffs(
 pub(crate) fn process_storage_diff(
     addr: &H160,
     diff: &std::collections::BTreeMap<H256, Diff<H256>>,
-    block_number: &Option<u32>,
-    transaction_hash: &Option<Vec<u8>>,
-    transaction_index: usize,
+    tx_info: (&Option<u32>, &Option<Vec<u8>>, usize),
+    keys: &[H256],
     columns: &mut StorageDiffs,
     schema: &Table,
 ) {
+    let (block_number, transaction_hash, transaction_index) = tx_info;
+    let index = schema.storage_decoder.as_ref().and_then(|x| x.slot_index(addr, keys));
     for (s, sub_diff) in diff.iter() {
         let (from, to) = match sub_diff {
             Diff::Same => continue,
@@ -83,6 +104,11 @@ pub(crate) fn process_storage_diff(
             Diff::Died(value) => (*value, H256::zero()),
             Diff::Changed(ChangedType { from, to }) => (*from, *to),
         };
+        let decoded = match &index {
+            Some(index) => index.decode(s, &[from, to]),
+            None => DecodedSlot { values: vec![None, None], ..Default::default() },
+        };
+        let mut values = decoded.values.into_iter();
         columns.n_rows += 1;
         store!(schema, columns, block_number, *block_number);
         store!(schema, columns, transaction_index, Some(transaction_index as u32));
@@ -91,5 +117,10 @@ pub(crate) fn process_storage_diff(
         store!(schema, columns, address, addr.as_bytes().to_vec());
         store!(schema, columns, from_value, from.as_bytes().to_vec());
         store!(schema, columns, to_value, to.as_bytes().to_vec());
+        store!(schema, columns, variable, decoded.variable);
+        store!(schema, columns, mapping_keys, decoded.mapping_keys);
+        store!(schema, columns, value_type, decoded.value_type);
+        store!(schema, columns, decoded_from_value, values.next().flatten());
+        store!(schema, columns, decoded_to_value, values.next().flatten());
     }
 }
@@ -28,6 +28,7 @@ pub struct TraceCalls {
     error: Vec<Option<String>>,
     tx_to_address: Vec<Vec<u8>>,
     tx_call_data: Vec<Vec<u8>>,
+    overrides_hash: Vec<Option<Vec<u8>>>,
     chain_id: Vec<u64>,
 }
 
@@ -49,24 +50,44 @@ impl Dataset for TraceCalls {
 impl CollectByBlock for TraceCalls {
     type Response = (u32, Vec<u8>, Vec<u8>, Vec<TransactionTrace>);
 
-    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
-        let traces: Vec<TransactionTrace> = source
-            .fetcher
-            .trace_call2(
-                request.ethers_contract()?,
-                request.call_data()?,
-                vec![TraceType::Trace],
-                Some(request.ethers_block_number()?),
-            )
-            .await?
-            .trace
-            .ok_or(CollectError::CollectError("traces missing".to_string()))?;
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        // parity's trace_call does not take overrides, so overridden calls use debug_traceCall
+        let traces: Vec<TransactionTrace> = match &query.call_overrides {
+            Some(overrides) => {
+                let transaction = TransactionRequest {
+                    to: Some(request.ethers_contract()?.into()),
+                    data: Some(request.call_data()?.into()),
+                    ..Default::default()
+                };
+                source
+                    .fetcher
+                    .geth_debug_trace_call_flat_calls(
+                        transaction,
+                        request.ethers_block_number()?,
+                        overrides.state_overrides.clone(),
+                        overrides.block_overrides.clone(),
+                    )
+                    .await?
+            }
+            None => source
+                .fetcher
+                .trace_call2(
+                    request.ethers_contract()?,
+                    request.call_data()?,
+                    vec![TraceType::Trace],
+                    Some(request.ethers_block_number()?),
+                )
+                .await?
+                .trace
+                .ok_or(CollectError::CollectError("traces missing".to_string()))?,
+        };
         Ok((request.block_number()? as u32, request.contract()?, request.call_data()?, traces))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let schema = query.schemas.get_schema(&Datatype::TraceCalls)?;
-        process_transaction_traces(response, columns, schema);
+        let overrides_hash = query.call_overrides.as_ref().map(|x| x.hash.clone());
+        process_transaction_traces(response, overrides_hash, columns, schema);
         Ok(())
     }
 }
@@ -77,6 +98,7 @@ impl CollectByTransaction for TraceCalls {
 
 fn process_transaction_traces(
     response: (u32, Vec<u8>, Vec<u8>, Vec<TransactionTrace>),
+    overrides_hash: Option<Vec<u8>>,
     columns: &mut TraceCalls,
     schema: &Table,
 ) {
@@ -99,6 +121,7 @@ fn process_transaction_traces(
         store!(schema, columns, error, trace.error.clone());
         store!(schema, columns, tx_to_address, contract.clone());
         store!(schema, columns, tx_call_data, call_data.clone());
+        store!(schema, columns, overrides_hash, overrides_hash.clone());
     }
 }
 
@@ -27,18 +27,61 @@ pub struct Traces {
     block_number: Vec<u32>,
     block_hash: Vec<Vec<u8>>,
     error: Vec<Option<String>>,
+    function_name: Vec<Option<String>>,
+    revert_reason: Vec<Option<String>>,
+    function_signature: Vec<Option<String>>,
+    signature_unique: Vec<Option<bool>>,
+    function_cols: std::collections::HashMap<String, Vec<Option<ethers_core::abi::Token>>>,
     chain_id: Vec<u64>,
 }
 
 #[async_trait::async_trait]
-impl Dataset for Traces {}
+impl Dataset for Traces {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "action_from",
+            "action_to",
+            "action_value",
+            "action_gas",
+            "action_input",
+            "action_call_type",
+            "action_init",
+            "action_reward_type",
+            "action_type",
+            "result_gas_used",
+            "result_output",
+            "result_code",
+            "result_address",
+            "trace_address",
+            "subtraces",
+            "transaction_index",
+            "transaction_hash",
+            "block_number",
+            "block_hash",
+            "error",
+            "chain_id",
+        ])
+    }
+
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::FromAddress, Dim::ToAddress]
+    }
+
+    fn block_range_parameters() -> Vec<Dim> {
+        vec![Dim::FromAddress, Dim::ToAddress]
+    }
+}
 
 #[async_trait::async_trait]
 impl CollectByBlock for Traces {
     type Response = Vec<Trace>;
 
     async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
-        source.fetcher.trace_block(request.block_number()?.into()).await
+        if request.block_range.is_some() {
+            source.fetcher.trace_filter(request.ethers_trace_filter()?).await
+        } else {
+            source.fetcher.trace_block(request.block_number()?.into()).await
+        }
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
@@ -87,6 +130,36 @@ pub(crate) fn process_traces(traces: &[Trace], columns: &mut Traces, schemas: &S
         store!(schema, columns, block_number, trace.block_number as u32);
         store!(schema, columns, block_hash, trace.block_hash.as_bytes().to_vec());
         store!(schema, columns, error, trace.error.clone());
+        let function_name = match &schema.function_decoder {
+            Some(decoder) => {
+                let (input, output) = match (&trace.action, &trace.result) {
+                    (Action::Call(action), Some(Res::Call(result))) => {
+                        (Some(action.input.as_ref()), Some(result.output.as_ref()))
+                    }
+                    (Action::Call(action), _) => (Some(action.input.as_ref()), None),
+                    _ => (None, None),
+                };
+                decoder.decode_call(&mut columns.function_cols, input, output)
+            }
+            None => None,
+        };
+        store!(schema, columns, function_name, function_name);
+        let revert_reason = match (&trace.error, &trace.result) {
+            (Some(_), Some(Res::Call(result))) => {
+                decode_revert_reason(&result.output, schema.function_decoder.as_ref())
+            }
+            _ => None,
+        };
+        store!(schema, columns, revert_reason, revert_reason);
+        let (function_signature, signature_unique) =
+            match (&schema.signature_database, &trace.action) {
+                (Some(database), Action::Call(action)) => {
+                    database.function_signature(&action.input)
+                }
+                _ => (None, None),
+            };
+        store!(schema, columns, function_signature, function_signature);
+        store!(schema, columns, signature_unique, signature_unique);
     }
     Ok(())
 }
@@ -1,6 +1,7 @@
 use crate::*;
 use ethers::prelude::*;
 use polars::prelude::*;
+use std::collections::HashMap;
 
 /// columns for transactions
 #[cryo_to_df::to_df(Datatype::Transactions)]
@@ -22,6 +23,11 @@ pub struct Transactions {
     max_priority_fee_per_gas: Vec<Option<u64>>,
     max_fee_per_gas: Vec<Option<u64>>,
     success: Vec<bool>,
+    function_name: Vec<Option<String>>,
+    revert_reason: Vec<Option<String>>,
+    function_signature: Vec<Option<String>>,
+    signature_unique: Vec<Option<bool>>,
+    function_cols: std::collections::HashMap<String, Vec<Option<ethers_core::abi::Token>>>,
     chain_id: Vec<u64>,
 }
 
@@ -30,11 +36,36 @@ impl Dataset for Transactions {
     fn aliases() -> Vec<&'static str> {
         vec!["txs"]
     }
+
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "transaction_hash",
+            "nonce",
+            "from_address",
+            "to_address",
+            "value",
+            "input",
+            "gas_limit",
+            "gas_used",
+            "gas_price",
+            "transaction_type",
+            "max_priority_fee_per_gas",
+            "max_fee_per_gas",
+            "success",
+            "chain_id",
+        ])
+    }
 }
 
+/// top level call output of reverted transactions, by transaction hash
+type RevertOutputs = HashMap<H256, Vec<u8>>;
+
 #[async_trait::async_trait]
 impl CollectByBlock for Transactions {
-    type Response = (Block<Transaction>, Option<Vec<TransactionReceipt>>, bool);
+    type Response =
+        (Block<Transaction>, Option<Vec<TransactionReceipt>>, bool, Option<RevertOutputs>);
 
     async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
         let block = source
@@ -48,18 +79,26 @@ impl CollectByBlock for Transactions {
         } else {
             None
         };
-        Ok((block, receipt, query.exclude_failed))
+        let revert_outputs = if schema.has_column("revert_reason") {
+            let traces = source.fetcher.trace_block(request.block_number()?.into()).await?;
+            Some(revert_outputs(traces))
+        } else {
+            None
+        };
+        Ok((block, receipt, query.exclude_failed, revert_outputs))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let schema = query.schemas.get_schema(&Datatype::Transactions)?;
-        let (block, receipts, exclude_failed) = response;
+        let (block, receipts, exclude_failed, mut revert_outputs) = response;
         match receipts {
             Some(receipts) => {
                 for (tx, receipt) in block.transactions.into_iter().zip(receipts) {
+                    let revert_output = revert_outputs.as_mut().and_then(|x| x.remove(&tx.hash));
                     process_transaction(
                         tx,
                         Some(receipt.clone()),
+                        revert_output,
                         columns,
                         schema,
                         exclude_failed,
@@ -68,7 +107,8 @@ impl CollectByBlock for Transactions {
             }
             None => {
                 for tx in block.transactions.into_iter() {
-                    process_transaction(tx, None, columns, schema, exclude_failed)?;
+                    let revert_output = revert_outputs.as_mut().and_then(|x| x.remove(&tx.hash));
+                    process_transaction(tx, None, revert_output, columns, schema, exclude_failed)?;
                 }
             }
         }
@@ -78,7 +118,7 @@ impl CollectByBlock for Transactions {
 
 #[async_trait::async_trait]
 impl CollectByTransaction for Transactions {
-    type Response = (Transaction, Option<TransactionReceipt>, bool);
+    type Response = (Transaction, Option<TransactionReceipt>, bool, Option<Vec<u8>>);
 
     async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
         let tx_hash = request.ethers_transaction_hash()?;
@@ -93,13 +133,19 @@ impl CollectByTransaction for Transactions {
         } else {
             None
         };
-        Ok((transaction, gas_used, query.exclude_failed))
+        let revert_output = if schema.has_column("revert_reason") {
+            let traces = source.fetcher.trace_transaction(tx_hash).await?;
+            revert_outputs(traces).remove(&tx_hash)
+        } else {
+            None
+        };
+        Ok((transaction, gas_used, query.exclude_failed, revert_output))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let schema = query.schemas.get_schema(&Datatype::Transactions)?;
-        let (transaction, receipt, exclude_failed) = response;
-        process_transaction(transaction, receipt, columns, schema, exclude_failed)?;
+        let (transaction, receipt, exclude_failed, revert_output) = response;
+        process_transaction(transaction, receipt, revert_output, columns, schema, exclude_failed)?;
         Ok(())
     }
 }
@@ -107,6 +153,7 @@ impl CollectByTransaction for Transactions {
 pub(crate) fn process_transaction(
     tx: Transaction,
     receipt: Option<TransactionReceipt>,
+    revert_output: Option<Vec<u8>>,
     columns: &mut Transactions,
     schema: &Table,
     exclude_failed: bool,
@@ -129,6 +176,23 @@ pub(crate) fn process_transaction(
     store!(schema, columns, to_address, tx.to.map(|x| x.as_bytes().to_vec()));
     store!(schema, columns, nonce, tx.nonce.as_u64());
     store!(schema, columns, value, tx.value);
+    let function_name = match &schema.function_decoder {
+        Some(decoder) => {
+            let input = tx.to.map(|_| tx.input.as_ref());
+            decoder.decode_call(&mut columns.function_cols, input, None)
+        }
+        None => None,
+    };
+    store!(schema, columns, function_name, function_name);
+    let revert_reason =
+        revert_output.and_then(|x| decode_revert_reason(&x, schema.function_decoder.as_ref()));
+    store!(schema, columns, revert_reason, revert_reason);
+    let (function_signature, signature_unique) = match (&schema.signature_database, tx.to) {
+        (Some(database), Some(_)) => database.function_signature(&tx.input),
+        _ => (None, None),
+    };
+    store!(schema, columns, function_signature, function_signature);
+    store!(schema, columns, signature_unique, signature_unique);
     store!(schema, columns, input, tx.input.to_vec());
     store!(schema, columns, gas_limit, tx.gas.as_u64());
     store!(schema, columns, success, success);
@@ -146,6 +210,18 @@ pub(crate) fn process_transaction(
     Ok(())
 }
 
+/// output of the top level call of each reverted transaction
+fn revert_outputs(traces: Vec<Trace>) -> RevertOutputs {
+    traces
+        .into_iter()
+        .filter(|trace| trace.trace_address.is_empty() && trace.error.is_some())
+        .filter_map(|trace| match (trace.transaction_hash, trace.result) {
+            (Some(tx_hash), Some(Res::Call(result))) => Some((tx_hash, result.output.to_vec())),
+            _ => None,
+        })
+        .collect()
+}
+
 fn tx_success(tx: &Transaction, receipt: &Option<TransactionReceipt>) -> R<bool> {
     if let Some(status) = receipt.as_ref().and_then(|x| x.status) {
         Ok(status.as_u64() == 1)
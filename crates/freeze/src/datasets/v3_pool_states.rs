@@ -0,0 +1,129 @@
+use crate::*;
+use ethers::prelude::*;
+use polars::prelude::*;
+
+/// columns for uniswap v3 pool states
+#[cryo_to_df::to_df(Datatype::V3PoolStates)]
+#[derive(Default)]
+pub struct V3PoolStates {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    pool: Vec<Vec<u8>>,
+    sqrt_price_x96: Vec<Option<U256>>,
+    tick: Vec<Option<i32>>,
+    liquidity: Vec<Option<U256>>,
+    fee_growth_global0_x128: Vec<Option<U256>>,
+    fee_growth_global1_x128: Vec<Option<U256>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for V3PoolStates {
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["pool", "block_number"])
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("latest".to_string())
+    }
+
+    fn required_parameters() -> Vec<Dim> {
+        vec![Dim::Contract]
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Address, Dim::Contract)].into_iter().collect())
+    }
+}
+
+/// block number, pool, slot0 output, liquidity, fee growth global 0, fee growth global 1
+type V3PoolStateResponse =
+    (u32, Vec<u8>, Option<Bytes>, Option<Bytes>, Option<Bytes>, Option<Bytes>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for V3PoolStates {
+    type Response = V3PoolStateResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let block_number = request.block_number()?;
+        let pool = request.ethers_contract()?;
+        let (slot0, liquidity, fee_growth0, fee_growth1) = futures::join!(
+            source.fetcher.call2(pool, FUNCTION_UNISWAP_V3_SLOT0.clone(), block_number.into()),
+            source.fetcher.call2(pool, FUNCTION_UNISWAP_V3_LIQUIDITY.clone(), block_number.into()),
+            source.fetcher.call2(
+                pool,
+                FUNCTION_UNISWAP_V3_FEE_GROWTH_GLOBAL0.clone(),
+                block_number.into()
+            ),
+            source.fetcher.call2(
+                pool,
+                FUNCTION_UNISWAP_V3_FEE_GROWTH_GLOBAL1.clone(),
+                block_number.into()
+            ),
+        );
+        Ok((
+            block_number as u32,
+            request.contract()?,
+            slot0.ok(),
+            liquidity.ok(),
+            fee_growth0.ok(),
+            fee_growth1.ok(),
+        ))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::V3PoolStates)?;
+        let (block_number, pool, slot0, liquidity, fee_growth0, fee_growth1) = response;
+        let (sqrt_price_x96, tick) = match slot0.as_deref().and_then(decode_slot0) {
+            Some((sqrt_price_x96, tick)) => (Some(sqrt_price_x96), Some(tick)),
+            None => (None, None),
+        };
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, block_number);
+        store!(schema, columns, pool, pool);
+        store!(schema, columns, sqrt_price_x96, sqrt_price_x96);
+        store!(schema, columns, tick, tick);
+        store!(schema, columns, liquidity, liquidity.and_then(|x| bytes_to_u256(&x)));
+        store!(
+            schema,
+            columns,
+            fee_growth_global0_x128,
+            fee_growth0.and_then(|x| bytes_to_u256(&x))
+        );
+        store!(
+            schema,
+            columns,
+            fee_growth_global1_x128,
+            fee_growth1.and_then(|x| bytes_to_u256(&x))
+        );
+        Ok(())
+    }
+}
+
+impl CollectByTransaction for V3PoolStates {
+    type Response = ();
+}
+
+/// decode (sqrtPriceX96, tick) from slot0 output, which is (sqrtPriceX96, tick, ...) in 32 byte
+/// words
+fn decode_slot0(output: &[u8]) -> Option<(U256, i32)> {
+    let sqrt_price_x96 = bytes_to_u256(output.get(0..32)?)?;
+    let tick = I256::from_raw(bytes_to_u256(output.get(32..64)?)?).low_i32();
+    Some((sqrt_price_x96, tick))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_slot0() {
+        let mut output = vec![0u8; 32 * 7];
+        output[31] = 100;
+        // tick of -10 as a sign extended word
+        output[32..64].copy_from_slice(&[0xff; 32]);
+        output[63] = 0xf6;
+        assert_eq!(decode_slot0(&output), Some((U256::from(100), -10)));
+        assert_eq!(decode_slot0(&output[..63]), None);
+    }
+}
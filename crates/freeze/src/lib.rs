@@ -11,10 +11,12 @@ mod collect;
 mod datasets;
 mod freeze;
 mod multi_datasets;
+mod stream;
 mod types;
 
 pub use collect::collect;
 pub use datasets::*;
 pub use freeze::freeze;
 pub use multi_datasets::*;
+pub use stream::{stream, StreamOptions};
 pub use types::*;
@@ -122,6 +122,7 @@ pub(crate) fn process_geth_diffs(
             .collect::<std::collections::HashSet<_>>()
             .into_iter()
             .collect();
+        let keys: Vec<H256> = addresses.iter().map(|address| H256::from(**address)).collect();
         for address in addresses.into_iter() {
             let (pre, post) = match (trace.pre.get(address), trace.post.get(address)) {
                 (Some(pre), Some(post)) => (pre, post),
@@ -139,7 +140,8 @@ pub(crate) fn process_geth_diffs(
                 add_nonces(address, pre.nonce, post.nonce, nonces, schema, index)?;
             }
             if let (Some(storages), Some(schema)) = (storages.as_mut(), storage_schema) {
-                add_storages(address, &pre.storage, &post.storage, storages, schema, index)?;
+                let (pre, post) = (&pre.storage, &post.storage);
+                add_storages(address, pre, post, &keys, storages, schema, index)?;
             }
         }
     }
@@ -226,6 +228,7 @@ fn add_storages(
     address: &H160,
     pre: &Option<BTreeMap<H256, H256>>,
     post: &Option<BTreeMap<H256, H256>>,
+    keys: &[H256],
     columns: &mut GethStorageDiffs,
     schema: &Table,
     index: &(Option<u32>, u32, Option<Vec<u8>>),
@@ -246,6 +249,7 @@ fn add_storages(
         .into_iter()
         .collect();
     let blank = H256::zero();
+    let decoder = schema.storage_decoder.as_ref().and_then(|x| x.slot_index(address, keys));
     for slot in slots.into_iter() {
         let (from, to) = match (pre.get(&slot), post.get(&slot)) {
             (Some(pre), Some(post)) => (pre, post),
@@ -253,6 +257,11 @@ fn add_storages(
             (None, Some(post)) => (&blank, post),
             (None, None) => (&blank, &blank),
         };
+        let decoded = match &decoder {
+            Some(decoder) => decoder.decode(&slot, &[*from, *to]),
+            None => DecodedSlot { values: vec![None, None], ..Default::default() },
+        };
+        let mut values = decoded.values.into_iter();
         columns.n_rows += 1;
         store!(schema, columns, block_number, *block_number);
         store!(schema, columns, transaction_index, Some(*transaction_index as u64));
@@ -261,6 +270,11 @@ fn add_storages(
         store!(schema, columns, slot, slot.as_bytes().to_vec());
         store!(schema, columns, from_value, from.as_bytes().to_vec());
         store!(schema, columns, to_value, to.as_bytes().to_vec());
+        store!(schema, columns, variable, decoded.variable);
+        store!(schema, columns, mapping_keys, decoded.mapping_keys);
+        store!(schema, columns, value_type, decoded.value_type);
+        store!(schema, columns, decoded_from_value, values.next().flatten());
+        store!(schema, columns, decoded_to_value, values.next().flatten());
     }
     Ok(())
 }
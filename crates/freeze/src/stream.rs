@@ -0,0 +1,332 @@
+use crate::{
+    dataframes, err, process_pending_transaction, summaries, summaries::StreamSummary,
+    CollectError, Datatype, ExecutionEnv, Fetcher, FileOutput, PendingTransaction,
+    PendingTransactions, Query, SchemaFunctions, Source, ToDataFrames,
+};
+use ethers::prelude::*;
+use futures::StreamExt;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::mpsc;
+
+/// Options for streaming live data
+#[derive(Clone, Debug)]
+pub struct StreamOptions {
+    /// websocket rpc url used for subscriptions
+    pub ws_url: String,
+    /// number of seconds covered by each output file
+    pub rotate_interval: u64,
+    /// number of seconds to stream before stopping, runs until interrupted if None
+    pub duration: Option<u64>,
+}
+
+/// stream pending transactions to time-partitioned files
+///
+/// each file covers `rotate_interval` seconds of first-seen timestamps, and is written one
+/// interval after its window closes so that inclusions observed in the meantime are recorded
+pub async fn stream(
+    query: &Query,
+    source: Arc<Source>,
+    sink: &FileOutput,
+    env: &ExecutionEnv,
+    options: &StreamOptions,
+) -> Result<(), CollectError> {
+    let datatypes: Vec<Datatype> = query.datatypes.iter().flat_map(|x| x.datatypes()).collect();
+    if datatypes != vec![Datatype::PendingTransactions] {
+        return Err(err("pending_transactions must be streamed on its own"))
+    }
+    if options.rotate_interval == 0 {
+        return Err(err("rotate interval must be greater than 0"))
+    }
+
+    if env.verbose >= 1 {
+        summaries::print_stream_intro(query, &source, sink, env, options);
+    }
+    if env.dry {
+        return Ok(())
+    }
+
+    let provider = Provider::<Ws>::connect(options.ws_url.as_str())
+        .await
+        .map_err(CollectError::ProviderError)?;
+    let ws = Fetcher { provider, semaphore: None, rate_limiter: None };
+    let mut pending_hashes = ws.subscribe_pending_txs().await?;
+    let mut new_blocks = ws.subscribe_blocks().await?;
+
+    let (tx_sender, mut tx_receiver) = mpsc::unbounded_channel();
+    let (block_sender, mut block_receiver) = mpsc::unbounded_channel();
+    let mut tracker = PendingTracker::new(options.rotate_interval * 1000);
+    let mut summary = StreamSummary::default();
+    let mut ticker = tokio::time::interval(Duration::from_secs(1));
+    let duration = options.duration;
+    let stop = async move {
+        match duration {
+            Some(duration) => tokio::time::sleep(Duration::from_secs(duration)).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(stop);
+    let interrupt = tokio::signal::ctrl_c();
+    tokio::pin!(interrupt);
+    let mut pending_open = true;
+    let mut blocks_open = true;
+
+    // the ticker is always ready, so closed subscriptions must end the loop explicitly
+    while pending_open || blocks_open {
+        tokio::select! {
+            tx_hash = pending_hashes.next(), if pending_open => match tx_hash {
+                Some(tx_hash) => {
+                    if tracker.insert(tx_hash, now_ms()) {
+                        let fetcher = source.fetcher.clone();
+                        let sender = tx_sender.clone();
+                        tokio::spawn(async move {
+                            let _ = sender.send((tx_hash, fetcher.get_transaction(tx_hash).await));
+                        });
+                    }
+                }
+                None => pending_open = false,
+            },
+            block = new_blocks.next(), if blocks_open => match block {
+                Some(block) => {
+                    if let Some(number) = block.number {
+                        let fetcher = source.fetcher.clone();
+                        let sender = block_sender.clone();
+                        tokio::spawn(async move {
+                            let _ = sender.send(fetcher.get_block(number.as_u64()).await);
+                        });
+                    }
+                }
+                None => blocks_open = false,
+            },
+            Some((tx_hash, result)) = tx_receiver.recv() => {
+                // transactions can leave the mempool before their body is fetched
+                if let Ok(Some(transaction)) = result {
+                    tracker.set_transaction(tx_hash, transaction)
+                }
+            }
+            Some(result) = block_receiver.recv() => {
+                match result {
+                    Ok(Some(block)) => tracker.mark_included(&block),
+                    Ok(None) => {}
+                    Err(e) => summary.errored.push(e),
+                }
+            }
+            _ = ticker.tick() => {
+                let windows = tracker.take_closed_windows(now_ms());
+                write_windows(windows, options, query, &source, sink, env, &mut summary);
+            }
+            _ = &mut stop => break,
+            _ = &mut interrupt => break,
+        }
+    }
+    if !pending_open && !blocks_open {
+        summary.errored.push(err("websocket subscriptions closed"));
+    }
+
+    let windows = tracker.take_all_windows();
+    write_windows(windows, options, query, &source, sink, env, &mut summary);
+
+    if env.verbose >= 1 {
+        summaries::print_stream_conclusion(&summary, env)
+    }
+    Ok(())
+}
+
+/// write windows to files, recording failures so that one bad write does not end the stream
+fn write_windows(
+    windows: Vec<(u64, Vec<(H256, PendingTransaction)>)>,
+    options: &StreamOptions,
+    query: &Query,
+    source: &Source,
+    sink: &FileOutput,
+    env: &ExecutionEnv,
+    summary: &mut StreamSummary,
+) {
+    for (window_start, rows) in windows.into_iter() {
+        if let Err(e) = write_window(window_start, rows, options, query, source, sink, env, summary)
+        {
+            summary.errored.push(e)
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_window(
+    window_start: u64,
+    rows: Vec<(H256, PendingTransaction)>,
+    options: &StreamOptions,
+    query: &Query,
+    source: &Source,
+    sink: &FileOutput,
+    env: &ExecutionEnv,
+    summary: &mut StreamSummary,
+) -> Result<(), CollectError> {
+    let datatype = Datatype::PendingTransactions;
+    let start = window_start / 1000;
+    let label = format!("{}_to_{}", start, start + options.rotate_interval - 1);
+    let path = sink.get_labeled_path(datatype, &label)?;
+    if path.exists() && !sink.overwrite {
+        if env.verbose >= 1 {
+            summaries::print_stream_file(&path, None)
+        }
+        summary.skipped.push(path);
+        return Ok(())
+    }
+
+    let n_rows = rows.len();
+    let schema = query.schemas.get_schema(&datatype)?;
+    let mut columns = PendingTransactions::default();
+    for (tx_hash, pending) in rows.into_iter() {
+        process_pending_transaction(tx_hash, pending, &mut columns, schema);
+    }
+    for (_, mut df) in columns.create_dfs(&query.schemas, source.chain_id)? {
+        dataframes::df_to_file(&mut df, &path, sink)
+            .map_err(|_| CollectError::CollectError("error writing file".to_string()))?;
+    }
+    if env.verbose >= 1 {
+        summaries::print_stream_file(&path, Some(n_rows))
+    }
+    summary.n_rows += n_rows as u64;
+    summary.written.push(path);
+    Ok(())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_millis() as u64).unwrap_or(0)
+}
+
+/// pending transactions grouped into windows by first-seen time
+struct PendingTracker {
+    window_ms: u64,
+    transactions: HashMap<H256, PendingTransaction>,
+    windows: BTreeMap<u64, Vec<H256>>,
+}
+
+impl PendingTracker {
+    fn new(window_ms: u64) -> Self {
+        Self { window_ms, transactions: HashMap::new(), windows: BTreeMap::new() }
+    }
+
+    /// track a newly seen transaction hash, returns false if already tracked
+    fn insert(&mut self, tx_hash: H256, seen_ms: u64) -> bool {
+        if self.transactions.contains_key(&tx_hash) {
+            return false
+        }
+        let pending = PendingTransaction { first_seen_timestamp_ms: seen_ms, ..Default::default() };
+        self.transactions.insert(tx_hash, pending);
+        let window_start = seen_ms - seen_ms % self.window_ms;
+        self.windows.entry(window_start).or_default().push(tx_hash);
+        true
+    }
+
+    fn set_transaction(&mut self, tx_hash: H256, transaction: Transaction) {
+        if let Some(pending) = self.transactions.get_mut(&tx_hash) {
+            pending.transaction = Some(transaction)
+        }
+    }
+
+    fn mark_included(&mut self, block: &Block<TxHash>) {
+        let block_number = match block.number {
+            Some(number) => number.as_u32(),
+            None => return,
+        };
+        for (index, tx_hash) in block.transactions.iter().enumerate() {
+            if let Some(pending) = self.transactions.get_mut(tx_hash) {
+                pending.inclusion = Some((block_number, index as u32))
+            }
+        }
+    }
+
+    /// remove windows that closed at least one window ago
+    fn take_closed_windows(&mut self, now_ms: u64) -> Vec<(u64, Vec<(H256, PendingTransaction)>)> {
+        let window_ms = self.window_ms;
+        let closed: Vec<u64> = self
+            .windows
+            .keys()
+            .take_while(|start| *start + 2 * window_ms <= now_ms)
+            .cloned()
+            .collect();
+        closed.into_iter().filter_map(|start| self.take_window(start)).collect()
+    }
+
+    fn take_all_windows(&mut self) -> Vec<(u64, Vec<(H256, PendingTransaction)>)> {
+        let starts: Vec<u64> = self.windows.keys().cloned().collect();
+        starts.into_iter().filter_map(|start| self.take_window(start)).collect()
+    }
+
+    fn take_window(&mut self, start: u64) -> Option<(u64, Vec<(H256, PendingTransaction)>)> {
+        let tx_hashes = self.windows.remove(&start)?;
+        let rows = tx_hashes
+            .into_iter()
+            .filter_map(|tx_hash| self.transactions.remove(&tx_hash).map(|x| (tx_hash, x)))
+            .collect();
+        Some((start, rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(x: u64) -> H256 {
+        H256::from_low_u64_be(x)
+    }
+
+    fn window_hashes(windows: &[(u64, Vec<(H256, PendingTransaction)>)]) -> Vec<(u64, Vec<H256>)> {
+        windows
+            .iter()
+            .map(|(start, rows)| (*start, rows.iter().map(|(tx_hash, _)| *tx_hash).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_pending_tracker_windows() {
+        let mut tracker = PendingTracker::new(1000);
+        assert!(tracker.insert(hash(1), 1500));
+        assert!(tracker.insert(hash(2), 1999));
+        assert!(tracker.insert(hash(3), 2000));
+        // hashes are only tracked at their first sighting
+        assert!(!tracker.insert(hash(1), 2500));
+
+        // a window is held for one extra interval to catch inclusions
+        assert!(tracker.take_closed_windows(2999).is_empty());
+        let closed = tracker.take_closed_windows(3000);
+        assert_eq!(window_hashes(&closed), vec![(1000, vec![hash(1), hash(2)])]);
+        assert_eq!(closed[0].1[0].1.first_seen_timestamp_ms, 1500);
+        assert!(tracker.take_closed_windows(3000).is_empty());
+
+        let remaining = tracker.take_all_windows();
+        assert_eq!(window_hashes(&remaining), vec![(2000, vec![hash(3)])]);
+        assert!(tracker.take_all_windows().is_empty());
+        assert!(tracker.transactions.is_empty());
+    }
+
+    #[test]
+    fn test_pending_tracker_updates() {
+        let mut tracker = PendingTracker::new(1000);
+        tracker.insert(hash(1), 0);
+        tracker.insert(hash(2), 0);
+
+        let transaction = Transaction { hash: hash(1), nonce: 7.into(), ..Default::default() };
+        tracker.set_transaction(hash(1), transaction.clone());
+        // bodies and inclusions of untracked transactions are ignored
+        tracker.set_transaction(hash(3), transaction);
+        let block = Block::<TxHash> {
+            number: Some(10.into()),
+            transactions: vec![hash(3), hash(2)],
+            ..Default::default()
+        };
+        tracker.mark_included(&block);
+
+        let rows: HashMap<H256, PendingTransaction> =
+            tracker.take_all_windows().into_iter().flat_map(|(_, rows)| rows).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[&hash(1)].transaction.as_ref().map(|tx| tx.nonce), Some(7.into()));
+        assert_eq!(rows[&hash(1)].inclusion, None);
+        assert!(rows[&hash(2)].transaction.is_none());
+        assert_eq!(rows[&hash(2)].inclusion, Some((10, 1)));
+    }
+}
@@ -106,6 +106,11 @@ pub trait Dataset: Sync + Send {
         false
     }
 
+    /// parameters that, when given, make dataset use block ranges instead of individual blocks
+    fn block_range_parameters() -> Vec<Dim> {
+        vec![]
+    }
+
     /// input arg aliases
     fn arg_aliases() -> Option<HashMap<Dim, Dim>> {
         None
@@ -13,6 +13,24 @@ pub fn bytes_to_u32(value: Bytes) -> Result<u32, CollectError> {
     }
 }
 
+/// convert a 32 byte word to an address, zero addresses and other values give None
+pub fn bytes_to_address(value: &[u8]) -> Option<H160> {
+    if value.len() == 32 && value[0..12].iter().all(|b| *b == 0) && value.iter().any(|b| *b != 0) {
+        Some(H160::from_slice(&value[12..32]))
+    } else {
+        None
+    }
+}
+
+/// convert a 32 byte word to U256, other lengths give None
+pub fn bytes_to_u256(value: &[u8]) -> Option<U256> {
+    if value.len() == 32 {
+        Some(U256::from_big_endian(value))
+    } else {
+        None
+    }
+}
+
 /// Converts data to Vec<u8>
 pub trait ToVecU8 {
     /// Convert to Vec<u8>
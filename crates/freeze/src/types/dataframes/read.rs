@@ -1,5 +1,11 @@
-use crate::ParseError;
+use crate::{Datatype, ParseError};
+use ethers::prelude::U256;
 use polars::prelude::*;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
 
 /// read single binary column of parquet file as Vec<u8>
 pub fn read_binary_column(path: &str, column: &str) -> Result<Vec<Vec<u8>>, ParseError> {
@@ -29,3 +35,263 @@ pub fn read_binary_column(path: &str, column: &str) -> Result<Vec<Vec<u8>>, Pars
         })
         .collect()
 }
+
+/// transfer replayed from a transfers file
+#[derive(Clone, Debug)]
+pub struct TransferRow {
+    /// block number of transfer
+    pub block_number: u32,
+    /// transaction index of transfer
+    pub transaction_index: u32,
+    /// log index of transfer
+    pub log_index: u32,
+    /// sender of transfer
+    pub from_address: Vec<u8>,
+    /// recipient of transfer
+    pub to_address: Vec<u8>,
+    /// amount of erc20 transfer, or token id of erc721 transfer
+    pub value: U256,
+}
+
+/// transfer files to replay, each token's transfers are read once and shared across partitions
+#[derive(Clone, Debug, Default)]
+pub struct TransferFiles {
+    /// paths of transfer files, or directories containing them
+    pub paths: Vec<PathBuf>,
+    loaded: Arc<Mutex<LoadedTransfers>>,
+}
+
+type LoadedTransfers = HashMap<(Datatype, Vec<u8>), Arc<Vec<TransferRow>>>;
+
+impl TransferFiles {
+    /// create from paths of transfer files or directories
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self { paths, loaded: Default::default() }
+    }
+
+    /// transfers of a token up to and including `max_block`, from erc20_transfers or
+    /// erc721_transfers files
+    pub fn read(
+        &self,
+        datatype: Datatype,
+        token: &[u8],
+        max_block: u32,
+    ) -> Result<Vec<TransferRow>, ParseError> {
+        let transfers = {
+            // held while reading, so concurrent partitions wait for one read of the files
+            let mut loaded = match self.loaded.lock() {
+                Ok(loaded) => loaded,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            match loaded.get(&(datatype, token.to_vec())) {
+                Some(transfers) => transfers.clone(),
+                None => {
+                    let value_column = match datatype {
+                        Datatype::Erc721Transfers => "token_id",
+                        _ => "value",
+                    };
+                    let files = find_transfer_files(&self.paths, datatype)?;
+                    if files.is_empty() {
+                        return Err(ParseError::ParseError(format!(
+                            "no {} files found to replay",
+                            datatype.name()
+                        )))
+                    }
+                    let transfers =
+                        Arc::new(read_transfers(&files, token, value_column, u32::MAX)?);
+                    loaded.insert((datatype, token.to_vec()), transfers.clone());
+                    transfers
+                }
+            }
+        };
+        // transfers are sorted, so those up to max_block are a prefix
+        let n_transfers = transfers.partition_point(|x| x.block_number <= max_block);
+        Ok(transfers[..n_transfers].to_vec())
+    }
+}
+
+/// expand paths of transfer files, directories are searched for parquet files of datatype
+pub fn find_transfer_files(
+    paths: &[PathBuf],
+    datatype: Datatype,
+) -> Result<Vec<PathBuf>, ParseError> {
+    let mut files = Vec::new();
+    for path in paths.iter() {
+        if path.is_dir() {
+            let entries = std::fs::read_dir(path)
+                .map_err(|_e| ParseError::ParseError("could not read directory".to_string()))?;
+            let mut dir_files: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.extension().map(|x| x == "parquet").unwrap_or(false) &&
+                        path.file_name()
+                            .and_then(|x| x.to_str())
+                            .map(|x| x.contains(&format!("__{}__", datatype.name())))
+                            .unwrap_or(false)
+                })
+                .collect();
+            dir_files.sort();
+            files.extend(dir_files);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+/// read transfers of a single token from erc20_transfers or erc721_transfers parquet files
+///
+/// `value_column` is the U256 column holding the transferred value, read from its `_binary` or
+/// `_string` encoding. Only transfers up to and including `max_block` are returned, sorted by
+/// their position in the chain.
+pub fn read_transfers(
+    files: &[PathBuf],
+    token: &[u8],
+    value_column: &str,
+    max_block: u32,
+) -> Result<Vec<TransferRow>, ParseError> {
+    let mut transfers = Vec::new();
+    for path in files.iter() {
+        let file = std::fs::File::open(path)
+            .map_err(|_e| ParseError::ParseError("could not open file path".to_string()))?;
+        let df = ParquetReader::new(file)
+            .finish()
+            .map_err(|_e| ParseError::ParseError("could not read transfers file".to_string()))?;
+        transfers.extend(read_transfer_rows(&df, token, value_column, max_block)?);
+    }
+    transfers.sort_by_key(|x| (x.block_number, x.transaction_index, x.log_index));
+    Ok(transfers)
+}
+
+fn read_transfer_rows(
+    df: &DataFrame,
+    token: &[u8],
+    value_column: &str,
+    max_block: u32,
+) -> Result<Vec<TransferRow>, ParseError> {
+    let u32_column = |name: &str| -> Result<Vec<Option<u32>>, ParseError> {
+        Ok(df
+            .column(name)
+            .and_then(|x| x.cast(&DataType::UInt32))
+            .map_err(|_e| ParseError::ParseError(format!("transfers missing column {}", name)))?
+            .u32()
+            .map_err(|_e| ParseError::ParseError(format!("could not read column {}", name)))?
+            .into_iter()
+            .collect())
+    };
+    let binary_column = |name: &str| -> Result<Vec<Option<Vec<u8>>>, ParseError> {
+        Ok(df
+            .column(name)
+            .map_err(|_e| ParseError::ParseError(format!("transfers missing column {}", name)))?
+            .binary()
+            .map_err(|_e| {
+                ParseError::ParseError(format!("column {} must be binary, not hex", name))
+            })?
+            .into_iter()
+            .map(|x| x.map(|x| x.to_vec()))
+            .collect())
+    };
+
+    let block_numbers = u32_column("block_number")?;
+    let transaction_indices = u32_column("transaction_index")?;
+    let log_indices = u32_column("log_index")?;
+    let tokens = binary_column("erc20")?;
+    let from_addresses = binary_column("from_address")?;
+    let to_addresses = binary_column("to_address")?;
+    let binary_name = format!("{}_binary", value_column);
+    let string_name = format!("{}_string", value_column);
+    let values: Vec<Option<U256>> = if df.column(&binary_name).is_ok() {
+        binary_column(&binary_name)?
+            .into_iter()
+            .map(|x| x.map(|x| U256::from_big_endian(&x)))
+            .collect()
+    } else {
+        df.column(&string_name)
+            .map_err(|_e| {
+                ParseError::ParseError(format!("transfers missing column {}", binary_name))
+            })?
+            .utf8()
+            .map_err(|_e| ParseError::ParseError(format!("could not read column {}", string_name)))?
+            .into_iter()
+            .map(|x| x.and_then(|x| U256::from_dec_str(x).ok()))
+            .collect()
+    };
+
+    let mut rows = Vec::new();
+    for i in 0..df.height() {
+        if tokens[i].as_deref() != Some(token) {
+            continue
+        }
+        if let (Some(bn), Some(ti), Some(li), Some(from), Some(to), Some(value)) = (
+            block_numbers[i],
+            transaction_indices[i],
+            log_indices[i],
+            from_addresses[i].clone(),
+            to_addresses[i].clone(),
+            values[i],
+        ) {
+            if bn <= max_block {
+                rows.push(TransferRow {
+                    block_number: bn,
+                    transaction_index: ti,
+                    log_index: li,
+                    from_address: from,
+                    to_address: to,
+                    value,
+                })
+            }
+        } else {
+            return Err(ParseError::ParseError("transfers file has missing values".to_string()))
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_transfers() {
+        let token = vec![1u8; 20];
+        let other = vec![2u8; 20];
+        let value = |x: u64| {
+            let mut bytes = [0u8; 32];
+            U256::from(x).to_big_endian(&mut bytes);
+            bytes.to_vec()
+        };
+        let mut df = df!(
+            "block_number" => [2u32, 1, 3, 1],
+            "transaction_index" => [0u32, 1, 0, 0],
+            "log_index" => [0u32, 3, 0, 1],
+            "erc20" => [token.clone(), token.clone(), token.clone(), other],
+            "from_address" => [vec![0u8; 20], vec![0u8; 20], vec![3u8; 20], vec![0u8; 20]],
+            "to_address" => [vec![3u8; 20], vec![4u8; 20], vec![4u8; 20], vec![3u8; 20]],
+            "value_binary" => [value(5), value(7), value(2), value(9)],
+        )
+        .expect("could not build dataframe");
+        let dir = std::env::temp_dir().join(format!("cryo_read_transfers_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("could not create dir");
+        let path = dir.join("ethereum__erc20_transfers__00000000_to_00000003.parquet");
+        let file = std::fs::File::create(&path).expect("could not create file");
+        ParquetWriter::new(file).finish(&mut df).expect("could not write file");
+
+        let files = find_transfer_files(std::slice::from_ref(&dir), Datatype::Erc20Transfers)
+            .expect("could not find files");
+        assert_eq!(files, vec![path]);
+        let transfers = read_transfers(&files, &token, "value", 2).expect("could not read");
+        let values: Vec<U256> = transfers.iter().map(|x| x.value).collect();
+        assert_eq!(values, vec![U256::from(7), U256::from(5)]);
+
+        // files are read once, later reads are served from memory
+        let transfer_files = TransferFiles::new(vec![dir.clone()]);
+        let read = |max_block| {
+            let transfers = transfer_files.read(Datatype::Erc20Transfers, &token, max_block);
+            transfers.expect("could not read").iter().map(|x| x.value).collect::<Vec<_>>()
+        };
+        assert_eq!(read(3), vec![U256::from(7), U256::from(5), U256::from(2)]);
+        std::fs::remove_dir_all(&dir).expect("could not remove dir");
+        assert_eq!(read(1), vec![U256::from(7)]);
+        assert!(transfer_files.read(Datatype::Erc721Transfers, &token, 3).is_err());
+    }
+}
@@ -69,6 +69,25 @@ macro_rules! define_datatypes {
                 }
             }
 
+            /// blocks per request for a partition, or None to request individual blocks
+            ///
+            /// partitions filtered by a block range parameter are requested as whole chunks
+            /// unless a filter request size is given
+            pub fn inner_request_size_for(&self, partition: &Partition, source: &Source) -> Option<u64> {
+                let block_range_parameters = match *self {
+                    $(Datatype::$datatype => $datatype::block_range_parameters(),)*
+                };
+                let dims = partition.dims();
+                if block_range_parameters.iter().any(|dim| dims.contains(dim)) {
+                    let chunk_size = partition.block_numbers.as_ref().map(|chunks| chunks.size().max(1));
+                    source.filter_request_size.or(chunk_size)
+                } else if self.use_block_ranges() {
+                    Some(source.inner_request_size)
+                } else {
+                    None
+                }
+            }
+
             /// aliases of datatype
             pub fn arg_aliases(&self) -> HashMap<Dim, Dim> {
                 match *self {
@@ -114,11 +133,7 @@ macro_rules! define_datatypes {
         ) -> Result<HashMap<Datatype, DataFrame>, CollectError> {
             let task = match datatype {
                 MetaDatatype::Scalar(datatype) => {
-                    let inner_request_size = if datatype.use_block_ranges() {
-                        Some(source.inner_request_size)
-                    } else {
-                        None
-                    };
+                    let inner_request_size = datatype.inner_request_size_for(&partition, &source);
                     match datatype {
                     $(
                         Datatype::$datatype => $datatype::collect_by_block(partition, source, query, inner_request_size),
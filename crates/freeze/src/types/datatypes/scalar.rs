@@ -4,34 +4,51 @@ use std::collections::HashMap;
 
 define_datatypes!(
     AddressAppearances,
+    AddressTransactions,
     BalanceDiffs,
     Balances,
+    BlockRewards,
     Blocks,
+    BundleSimulations,
+    ChainlinkRounds,
     CodeDiffs,
     Codes,
     Contracts,
+    DexLiquidity,
+    DexSwaps,
+    EnsNames,
     Erc20Balances,
+    Erc20Holders,
     Erc20Metadata,
     Erc20Supplies,
     Erc20Transfers,
+    Erc4626Vaults,
     Erc721Metadata,
+    Erc721Owners,
     Erc721Transfers,
     EthCalls,
     GethCodeDiffs,
+    GethCustomTraces,
     GethBalanceDiffs,
     GethStorageDiffs,
     GethNonceDiffs,
+    GethOpcodeTraces,
+    GethPrestate,
+    GethTraceLogs,
     GethTraces,
     Logs,
     NativeTransfers,
     NonceDiffs,
     Nonces,
+    PendingTransactions,
+    Proxies,
     Slots,
     Prices,
     StorageDiffs,
     Traces,
     TraceCalls,
     Transactions,
+    V3PoolStates,
     VmTraces,
 );
 
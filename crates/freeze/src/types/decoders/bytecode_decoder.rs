@@ -0,0 +1,330 @@
+use std::collections::BTreeSet;
+
+/// columns derived from bytecode by analyze_bytecode()
+pub const BYTECODE_COLUMNS: [&str; 7] = [
+    "code_size",
+    "function_selectors",
+    "token_standards",
+    "compiler",
+    "metadata_hash",
+    "has_selfdestruct",
+    "has_delegatecall",
+];
+
+/// properties of contract bytecode, derived without any rpc calls
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BytecodeAnalysis {
+    /// size of code in bytes
+    pub code_size: u32,
+    /// function selectors compared against in the dispatcher, sorted
+    pub function_selectors: Vec<[u8; 4]>,
+    /// token standards whose functions are all present in the dispatcher
+    pub token_standards: Vec<&'static str>,
+    /// compiler and version from the metadata, e.g. `solc 0.8.19` or `vyper 0.3.10`
+    pub compiler: Option<String>,
+    /// source metadata hash, e.g. `ipfs://Qm..` or `bzzr1://..`
+    pub metadata_hash: Option<String>,
+    /// whether code contains a SELFDESTRUCT opcode outside of push data
+    pub has_selfdestruct: bool,
+    /// whether code contains a DELEGATECALL opcode
+    pub has_delegatecall: bool,
+}
+
+impl BytecodeAnalysis {
+    /// function selectors as a comma separated list of hex strings
+    pub fn selectors_string(&self) -> String {
+        self.function_selectors
+            .iter()
+            .map(|x| prefix_hex::encode(x.to_vec()))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// token standards as a comma separated list
+    pub fn token_standards_string(&self) -> String {
+        self.token_standards.join(",")
+    }
+}
+
+const OP_EQ: u8 = 0x14;
+const OP_XOR: u8 = 0x18;
+const OP_PUSH1: u8 = 0x60;
+const OP_PUSH3: u8 = 0x62;
+const OP_PUSH4: u8 = 0x63;
+const OP_PUSH32: u8 = 0x7f;
+const OP_DUP1: u8 = 0x80;
+const OP_DUP16: u8 = 0x8f;
+const OP_DELEGATECALL: u8 = 0xf4;
+const OP_SELFDESTRUCT: u8 = 0xff;
+
+const ERC20_SELECTORS: [[u8; 4]; 6] = [
+    [0x18, 0x16, 0x0d, 0xdd], // totalSupply()
+    [0x70, 0xa0, 0x82, 0x31], // balanceOf(address)
+    [0xa9, 0x05, 0x9c, 0xbb], // transfer(address,uint256)
+    [0x23, 0xb8, 0x72, 0xdd], // transferFrom(address,address,uint256)
+    [0x09, 0x5e, 0xa7, 0xb3], // approve(address,uint256)
+    [0xdd, 0x62, 0xed, 0x3e], // allowance(address,address)
+];
+
+const ERC721_SELECTORS: [[u8; 4]; 6] = [
+    [0x70, 0xa0, 0x82, 0x31], // balanceOf(address)
+    [0x63, 0x52, 0x21, 0x1e], // ownerOf(uint256)
+    [0x42, 0x84, 0x2e, 0x0e], // safeTransferFrom(address,address,uint256)
+    [0x08, 0x18, 0x12, 0xfc], // getApproved(uint256)
+    [0xa2, 0x2c, 0xb4, 0x65], // setApprovalForAll(address,bool)
+    [0xe9, 0x85, 0xe9, 0xc5], // isApprovedForAll(address,address)
+];
+
+const ERC1155_SELECTORS: [[u8; 4]; 6] = [
+    [0x00, 0xfd, 0xd5, 0x8e], // balanceOf(address,uint256)
+    [0x4e, 0x12, 0x73, 0xf4], // balanceOfBatch(address[],uint256[])
+    [0xf2, 0x42, 0x43, 0x2a], // safeTransferFrom(address,address,uint256,uint256,bytes)
+    [0x2e, 0xb2, 0xc2, 0xd6], // safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)
+    [0xa2, 0x2c, 0xb4, 0x65], // setApprovalForAll(address,bool)
+    [0xe9, 0x85, 0xe9, 0xc5], // isApprovedForAll(address,address)
+];
+
+/// analyze deployed bytecode of a contract
+pub fn analyze_bytecode(code: &[u8]) -> BytecodeAnalysis {
+    let (instructions, metadata) = match split_metadata(code) {
+        Some((instructions, metadata)) => (instructions, Some(metadata)),
+        None => (code, None),
+    };
+    let (compiler, metadata_hash) = metadata.map(decode_metadata).unwrap_or_default();
+
+    let mut selectors = BTreeSet::new();
+    let mut has_selfdestruct = false;
+    let mut has_delegatecall = false;
+    let ops = disassemble(instructions);
+    for (i, (op, data)) in ops.iter().enumerate() {
+        match *op {
+            OP_SELFDESTRUCT => has_selfdestruct = true,
+            OP_DELEGATECALL => has_delegatecall = true,
+            // dispatchers compare the selector with `PUSH4 sel EQ`, optionally with a DUP
+            // in between, or with XOR in vyper. solc shortens selectors with a leading zero
+            OP_PUSH3 | OP_PUSH4 => {
+                let next = ops.get(i + 1).map(|x| x.0);
+                let after = ops.get(i + 2).map(|x| x.0);
+                let is_comparison = |op: Option<u8>| op == Some(OP_EQ) || op == Some(OP_XOR);
+                let is_dup = next.map(|x| (OP_DUP1..=OP_DUP16).contains(&x)).unwrap_or(false);
+                if is_comparison(next) || (is_dup && is_comparison(after)) {
+                    let mut selector = [0u8; 4];
+                    selector[4 - data.len()..].copy_from_slice(data);
+                    selectors.insert(selector);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut token_standards = Vec::new();
+    for (standard, required) in
+        [("erc20", ERC20_SELECTORS), ("erc721", ERC721_SELECTORS), ("erc1155", ERC1155_SELECTORS)]
+    {
+        if required.iter().all(|x| selectors.contains(x)) {
+            token_standards.push(standard)
+        }
+    }
+
+    BytecodeAnalysis {
+        code_size: code.len() as u32,
+        function_selectors: selectors.into_iter().collect(),
+        token_standards,
+        compiler,
+        metadata_hash,
+        has_selfdestruct,
+        has_delegatecall,
+    }
+}
+
+/// split opcodes into (opcode, push data), truncated push data at the end of code is kept
+fn disassemble(code: &[u8]) -> Vec<(u8, &[u8])> {
+    let mut ops = Vec::new();
+    let mut i = 0;
+    while i < code.len() {
+        let op = code[i];
+        let n_data =
+            if (OP_PUSH1..=OP_PUSH32).contains(&op) { (op - OP_PUSH1 + 1) as usize } else { 0 };
+        let end = (i + 1 + n_data).min(code.len());
+        ops.push((op, &code[i + 1..end]));
+        i = end;
+    }
+    ops
+}
+
+/// split trailing cbor metadata from code, the last two bytes of code give the metadata length
+fn split_metadata(code: &[u8]) -> Option<(&[u8], &[u8])> {
+    if code.len() < 2 {
+        return None
+    }
+    let length = u16::from_be_bytes([code[code.len() - 2], code[code.len() - 1]]) as usize;
+    if length == 0 || length + 2 > code.len() {
+        return None
+    }
+    let start = code.len() - 2 - length;
+    let metadata = &code[start..code.len() - 2];
+    // metadata is a cbor map
+    if metadata[0] & 0xe0 != 0xa0 {
+        return None
+    }
+    Some((&code[..start], metadata))
+}
+
+/// decode compiler version and source hash from cbor metadata
+fn decode_metadata(metadata: &[u8]) -> (Option<String>, Option<String>) {
+    let mut reader = CborReader { data: metadata, position: 0 };
+    let mut compiler = None;
+    let mut metadata_hash = None;
+    let n_entries = match reader.read_header() {
+        Some((5, n_entries)) => n_entries,
+        _ => return (None, None),
+    };
+    for _ in 0..n_entries {
+        let key = match reader.read_value() {
+            Some(CborValue::Text(key)) => key,
+            _ => break,
+        };
+        let value = match reader.read_value() {
+            Some(value) => value,
+            None => break,
+        };
+        match (key.as_str(), value) {
+            ("ipfs", CborValue::Bytes(hash)) => {
+                metadata_hash = Some(format!("ipfs://{}", base58_encode(&hash)))
+            }
+            ("bzzr0" | "bzzr1", CborValue::Bytes(hash)) => {
+                metadata_hash =
+                    Some(format!("{}://{}", key, prefix_hex::encode(hash).trim_start_matches("0x")))
+            }
+            ("solc", CborValue::Bytes(version)) if version.len() == 3 => {
+                compiler = Some(format!("solc {}.{}.{}", version[0], version[1], version[2]))
+            }
+            ("solc", CborValue::Text(version)) => compiler = Some(format!("solc {}", version)),
+            ("vyper", CborValue::Array(version)) => {
+                let version: Vec<String> = version.iter().map(|x| x.to_string()).collect();
+                compiler = Some(format!("vyper {}", version.join(".")))
+            }
+            _ => {}
+        }
+    }
+    (compiler, metadata_hash)
+}
+
+enum CborValue {
+    Uint,
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<u64>),
+    Simple,
+}
+
+/// minimal reader for the subset of cbor used by compiler metadata
+struct CborReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl CborReader<'_> {
+    /// read major type and argument of next item
+    fn read_header(&mut self) -> Option<(u8, u64)> {
+        let initial = *self.data.get(self.position)?;
+        self.position += 1;
+        let major = initial >> 5;
+        let argument = match initial & 0x1f {
+            n @ 0..=23 => n as u64,
+            n @ 24..=27 => {
+                let size = 1usize << (n - 24);
+                let bytes = self.data.get(self.position..self.position + size)?;
+                self.position += size;
+                bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64)
+            }
+            _ => return None,
+        };
+        Some((major, argument))
+    }
+
+    fn read_value(&mut self) -> Option<CborValue> {
+        let (major, argument) = self.read_header()?;
+        match major {
+            0 => Some(CborValue::Uint),
+            2 | 3 => {
+                let end = self.position.checked_add(argument as usize)?;
+                let bytes = self.data.get(self.position..end)?.to_vec();
+                self.position = end;
+                if major == 2 {
+                    Some(CborValue::Bytes(bytes))
+                } else {
+                    String::from_utf8(bytes).ok().map(CborValue::Text)
+                }
+            }
+            4 => {
+                let mut items = Vec::new();
+                for _ in 0..argument {
+                    match self.read_header()? {
+                        (0, value) => items.push(value),
+                        _ => return None,
+                    }
+                }
+                Some(CborValue::Array(items))
+            }
+            7 => Some(CborValue::Simple),
+            _ => None,
+        }
+    }
+}
+
+fn base58_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    let mut digits: Vec<u8> = Vec::new();
+    for byte in data.iter() {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let n_zeros = data.iter().take_while(|x| **x == 0).count();
+    std::iter::repeat_n(b'1', n_zeros)
+        .chain(digits.iter().rev().map(|x| ALPHABET[*x as usize]))
+        .map(|x| x as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_bytecode() {
+        // dispatcher for transfer(), a delegatecall, then solc 0.8.19 metadata with an ipfs hash
+        let mut code =
+            prefix_hex::decode::<Vec<u8>>("0x60003560e01c8063a9059cbb14610011575b6000f45b00")
+                .expect("invalid code");
+        let mut metadata = vec![0xa2, 0x64];
+        metadata.extend(b"ipfs");
+        metadata.extend([0x58, 0x22, 0x12, 0x20]);
+        metadata.extend([0u8; 32]);
+        metadata.extend([0x64]);
+        metadata.extend(b"solc");
+        metadata.extend([0x43, 0x00, 0x08, 0x13]);
+        code.extend(&metadata);
+        code.extend((metadata.len() as u16).to_be_bytes());
+
+        let analysis = analyze_bytecode(&code);
+        assert_eq!(analysis.code_size, code.len() as u32);
+        assert_eq!(analysis.selectors_string(), "0xa9059cbb");
+        assert_eq!(analysis.compiler, Some("solc 0.8.19".to_string()));
+        assert_eq!(
+            analysis.metadata_hash,
+            Some("ipfs://QmNLei78zWmzUdbeRB3CiUfAizWUrbeeZh5K1rhAQKCh51".to_string())
+        );
+        assert!(analysis.has_delegatecall);
+        assert!(!analysis.has_selfdestruct);
+        assert!(analysis.token_standards.is_empty());
+    }
+}
@@ -0,0 +1,291 @@
+use ethers::prelude::*;
+use ethers_core::abi::{self, HumanReadableParser, ParamType, Token};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use crate::{ColumnEncoding, ToVecHex};
+
+/// container for function call decoding context
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionDecoder {
+    /// functions to decode, by selector
+    pub functions: HashMap<[u8; 4], abi::Function>,
+    /// custom errors to decode from revert data, by selector
+    pub errors: HashMap<[u8; 4], abi::ethabi::AbiError>,
+    /// decoded columns and their abi types, None if functions disagree on the type
+    columns: Vec<(String, Option<ParamType>)>,
+}
+
+impl FunctionDecoder {
+    /// create a new FunctionDecoder from function signatures, and optionally custom errors
+    /// ex: FunctionDecoder::new(vec!["function transfer(address to, uint256 amount) returns
+    /// (bool)".to_string(), "error InsufficientBalance(uint256 available)".to_string()])
+    pub fn new(function_signatures: Vec<String>) -> Result<Self, String> {
+        let mut functions = Vec::new();
+        let mut errors = Vec::new();
+        for signature in function_signatures.iter() {
+            let signature = signature.trim();
+            if signature.starts_with("error ") {
+                match HumanReadableParser::parse_error(signature) {
+                    Ok(error) => errors.push(error),
+                    Err(e) => {
+                        return Err(format!("incorrectly formatted error {}: {}", signature, e))
+                    }
+                }
+                continue
+            }
+            let full_signature = if signature.starts_with("function ") {
+                signature.to_string()
+            } else {
+                format!("function {}", signature)
+            };
+            match HumanReadableParser::parse_function(full_signature.as_str()) {
+                Ok(function) => functions.push(function),
+                Err(e) => {
+                    return Err(format!("incorrectly formatted function {} (expect something like function transfer(address to, uint256 amount) returns (bool)) err: {}", signature, e))
+                }
+            }
+        }
+        Ok(Self::from_functions(functions, errors))
+    }
+
+    /// create a new FunctionDecoder from the functions of a json abi
+    pub fn from_abi_json(abi_json: &str) -> Result<Self, String> {
+        let contract: abi::Abi =
+            serde_json::from_str(abi_json).map_err(|e| format!("could not parse abi: {}", e))?;
+        let functions = contract.functions().cloned().collect();
+        Ok(Self::from_functions(functions, contract.errors().cloned().collect()))
+    }
+
+    fn from_functions(functions: Vec<abi::Function>, errors: Vec<abi::ethabi::AbiError>) -> Self {
+        let mut columns: Vec<(String, Option<ParamType>)> = Vec::new();
+        for function in functions.iter() {
+            let inputs = function.inputs.iter().map(|x| (&x.name, &x.kind));
+            let outputs = function.outputs.iter().map(|x| (&x.name, &x.kind));
+            let names = param_column_names("input", inputs.clone().map(|x| x.0))
+                .into_iter()
+                .zip(inputs.map(|x| x.1))
+                .chain(
+                    param_column_names("output", outputs.clone().map(|x| x.0))
+                        .into_iter()
+                        .zip(outputs.map(|x| x.1)),
+                );
+            for (name, kind) in names {
+                match columns.iter_mut().find(|(column, _)| *column == name) {
+                    Some((_, column_kind)) => {
+                        if column_kind.as_ref() != Some(kind) {
+                            *column_kind = None
+                        }
+                    }
+                    None => columns.push((name, Some(kind.clone()))),
+                }
+            }
+        }
+        let functions = functions.into_iter().map(|x| (x.short_signature(), x)).collect();
+        let errors = errors
+            .into_iter()
+            .map(|x| {
+                let mut selector = [0u8; 4];
+                selector.copy_from_slice(&x.signature().as_bytes()[..4]);
+                (selector, x)
+            })
+            .collect();
+        Self { functions, errors, columns }
+    }
+
+    /// names of the decoded columns
+    pub fn column_names(&self) -> Vec<String> {
+        self.columns.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// decode a call into columns, pushing a value or null to every decoded column
+    ///
+    /// returns the function name if the selector matched and the input could be decoded
+    pub fn decode_call(
+        &self,
+        columns: &mut HashMap<String, Vec<Option<Token>>>,
+        input: Option<&[u8]>,
+        output: Option<&[u8]>,
+    ) -> Option<String> {
+        let mut values: HashMap<String, Token> = HashMap::new();
+        let function = input
+            .filter(|input| input.len() >= 4)
+            .and_then(|input| {
+                let mut selector = [0u8; 4];
+                selector.copy_from_slice(&input[..4]);
+                self.functions.get(&selector).map(|function| (function, &input[4..]))
+            })
+            .and_then(|(function, data)| {
+                function.decode_input(data).ok().map(|tokens| (function, tokens))
+            });
+        if let Some((function, tokens)) = &function {
+            let names = param_column_names("input", function.inputs.iter().map(|x| &x.name));
+            values.extend(names.into_iter().zip(tokens.iter().cloned()));
+            if let Some(tokens) = output.and_then(|x| function.decode_output(x).ok()) {
+                let names = param_column_names("output", function.outputs.iter().map(|x| &x.name));
+                values.extend(names.into_iter().zip(tokens));
+            }
+        }
+        for (name, _) in self.columns.iter() {
+            columns.entry(name.clone()).or_default().push(values.remove(name));
+        }
+        function.map(|(function, _)| function.name.clone())
+    }
+
+    /// convert decoded columns into series, using a null column for any missing column
+    pub fn make_series(
+        &self,
+        mut columns: HashMap<String, Vec<Option<Token>>>,
+        n_rows: usize,
+        binary_type: &ColumnEncoding,
+    ) -> Vec<Series> {
+        let mut series = Vec::new();
+        for (name, kind) in self.columns.iter() {
+            let data = columns.remove(name).unwrap_or_else(|| vec![None; n_rows]);
+            let name = name.as_str();
+            match kind {
+                Some(ParamType::Uint(size)) if *size <= 64 => {
+                    let data: Vec<Option<u64>> = data
+                        .into_iter()
+                        .map(|x| x.and_then(|x| x.into_uint()).and_then(uint_to_u64))
+                        .collect();
+                    series.push(Series::new(name, data))
+                }
+                Some(ParamType::Int(size)) if *size <= 64 => {
+                    let data: Vec<Option<i64>> = data
+                        .into_iter()
+                        .map(|x| x.and_then(|x| x.into_int()).and_then(int_to_i64))
+                        .collect();
+                    series.push(Series::new(name, data))
+                }
+                Some(ParamType::Bool) => {
+                    let data: Vec<Option<bool>> =
+                        data.into_iter().map(|x| x.and_then(|x| x.into_bool())).collect();
+                    series.push(Series::new(name, data))
+                }
+                Some(ParamType::Address) |
+                Some(ParamType::Bytes) |
+                Some(ParamType::FixedBytes(_)) => {
+                    let data: Vec<Option<Vec<u8>>> = data
+                        .into_iter()
+                        .map(|x| {
+                            x.and_then(|x| match x {
+                                Token::Address(address) => Some(address.as_bytes().to_vec()),
+                                Token::Bytes(bytes) | Token::FixedBytes(bytes) => Some(bytes),
+                                _ => None,
+                            })
+                        })
+                        .collect();
+                    match binary_type {
+                        ColumnEncoding::Hex => series.push(Series::new(name, data.to_vec_hex())),
+                        ColumnEncoding::Binary => series.push(Series::new(name, data)),
+                    }
+                }
+                // large integers, strings, arrays, tuples, and columns of mixed type
+                _ => {
+                    let data: Vec<Option<String>> =
+                        data.into_iter().map(|x| x.map(|x| token_to_string(&x))).collect();
+                    series.push(Series::new(name, data))
+                }
+            }
+        }
+        series
+    }
+}
+
+/// convert a small uint, null if input was not cleanly encoded and does not fit into a u64
+fn uint_to_u64(value: U256) -> Option<u64> {
+    (value <= U256::from(u64::MAX)).then(|| value.as_u64())
+}
+
+/// convert a small int, null if input was not cleanly encoded and does not fit into an i64
+fn int_to_i64(value: U256) -> Option<i64> {
+    let value = I256::from_raw(value);
+    (value >= I256::from(i64::MIN) && value <= I256::from(i64::MAX)).then(|| value.low_i64())
+}
+
+/// column names of function params, unnamed params are named by their position
+fn param_column_names<'a>(prefix: &str, names: impl Iterator<Item = &'a String>) -> Vec<String> {
+    names
+        .enumerate()
+        .map(|(i, name)| {
+            if name.is_empty() {
+                format!("{}_{}", prefix, i)
+            } else {
+                format!("{}_{}", prefix, name)
+            }
+        })
+        .collect()
+}
+
+/// readable representation of a token, with decimal integers and 0x prefixed bytes
+pub(crate) fn token_to_string(token: &Token) -> String {
+    match token {
+        Token::Address(address) => format!("{:?}", address),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => prefix_hex::encode(bytes.clone()),
+        Token::Uint(value) => value.to_string(),
+        Token::Int(value) => I256::from_raw(*value).to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => value.clone(),
+        Token::Array(tokens) | Token::FixedArray(tokens) => {
+            format!("[{}]", tokens.iter().map(token_to_string).collect::<Vec<_>>().join(","))
+        }
+        Token::Tuple(tokens) => {
+            format!("({})", tokens.iter().map(token_to_string).collect::<Vec<_>>().join(","))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_call() {
+        let decoder = FunctionDecoder::new(vec![
+            "transfer(address to, uint256 amount) returns (bool)".to_string(),
+            "function approve(address, uint256) returns (bool)".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(
+            decoder.column_names(),
+            vec!["input_to", "input_amount", "output_0", "input_0", "input_1"]
+        );
+
+        let mut columns = HashMap::new();
+        let to = H160::from_low_u64_be(1);
+        let mut input = prefix_hex::decode::<Vec<u8>>("0xa9059cbb").unwrap();
+        input.extend(abi::encode(&[Token::Address(to), Token::Uint(U256::from(5))]));
+        let output = abi::encode(&[Token::Bool(true)]);
+        let name = decoder.decode_call(&mut columns, Some(&input), Some(&output));
+        assert_eq!(name, Some("transfer".to_string()));
+
+        let name = decoder.decode_call(&mut columns, Some(&[0, 0, 0, 0]), None);
+        assert_eq!(name, None);
+
+        assert_eq!(columns["input_to"], vec![Some(Token::Address(to)), None]);
+        assert_eq!(columns["output_0"], vec![Some(Token::Bool(true)), None]);
+        assert_eq!(columns["input_1"], vec![None, None]);
+    }
+
+    #[test]
+    fn test_make_series_dirty_words() {
+        let decoder = FunctionDecoder::new(vec!["f(uint8 a, int8 b)".to_string()]).unwrap();
+        let selector = decoder.functions.keys().next().cloned().unwrap();
+
+        let mut columns = HashMap::new();
+        let mut clean = selector.to_vec();
+        clean.extend(abi::encode(&[Token::Uint(U256::from(7)), Token::Int(U256::MAX)]));
+        // decoding does not validate that words fit their type, so high bits can be dirty
+        let mut dirty = selector.to_vec();
+        dirty.extend(abi::encode(&[Token::Uint(U256::MAX), Token::Uint(U256::one() << 64)]));
+        decoder.decode_call(&mut columns, Some(&clean), None);
+        decoder.decode_call(&mut columns, Some(&dirty), None);
+
+        let series = decoder.make_series(columns, 2, &ColumnEncoding::Hex);
+        let a: Vec<Option<u64>> = series[0].u64().unwrap().into_iter().collect();
+        let b: Vec<Option<i64>> = series[1].i64().unwrap().into_iter().collect();
+        assert_eq!(a, vec![Some(7), None]);
+        assert_eq!(b, vec![Some(-1), None]);
+    }
+}
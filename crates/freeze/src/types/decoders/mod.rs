@@ -1,3 +1,18 @@
 /// log decoder
 pub mod log_decoder;
 pub use log_decoder::*;
+/// bytecode decoder
+pub mod bytecode_decoder;
+pub use bytecode_decoder::*;
+/// function decoder
+pub mod function_decoder;
+pub use function_decoder::*;
+/// revert decoder
+pub mod revert_decoder;
+pub use revert_decoder::*;
+/// signature database
+pub mod signature_database;
+pub use signature_database::*;
+/// storage decoder
+pub mod storage_decoder;
+pub use storage_decoder::*;
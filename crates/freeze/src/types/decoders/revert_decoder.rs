@@ -0,0 +1,101 @@
+use ethers::prelude::*;
+use ethers_core::abi::{decode, ParamType};
+
+use super::function_decoder::token_to_string;
+use crate::FunctionDecoder;
+
+/// selector of Error(string)
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// selector of Panic(uint256)
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// decode the reason of a revert from its output data
+///
+/// gives the message of `Error(string)`, the code and meaning of `Panic(uint256)`, custom errors
+/// known to the decoder as `Name(arg,..)`, and the selector of any other error. empty revert data
+/// gives None
+pub fn decode_revert_reason(data: &[u8], decoder: Option<&FunctionDecoder>) -> Option<String> {
+    if data.is_empty() {
+        return None
+    }
+    if data.len() < 4 {
+        return Some(format!("unknown error {}", prefix_hex::encode(data.to_vec())))
+    }
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&data[..4]);
+    let params = &data[4..];
+    match selector {
+        ERROR_SELECTOR => {
+            let message = decode(&[ParamType::String], params).ok()?.into_iter().next()?;
+            message.into_string()
+        }
+        PANIC_SELECTOR => {
+            let code = decode(&[ParamType::Uint(256)], params).ok()?.into_iter().next()?;
+            let code = code.into_uint()?;
+            Some(format!("Panic({:#04x}): {}", code, panic_description(code)))
+        }
+        _ => {
+            let error = decoder.and_then(|decoder| decoder.errors.get(&selector));
+            match error.and_then(|error| error.decode(params).ok().map(|x| (error, x))) {
+                Some((error, tokens)) => {
+                    let args: Vec<String> = tokens.iter().map(token_to_string).collect();
+                    Some(format!("{}({})", error.name, args.join(",")))
+                }
+                None => Some(format!("unknown error {}", prefix_hex::encode(selector.to_vec()))),
+            }
+        }
+    }
+}
+
+/// meaning of solidity panic codes
+fn panic_description(code: U256) -> &'static str {
+    if code > U256::from(u8::MAX) {
+        return "unknown panic"
+    }
+    match code.as_u32() {
+        0x00 => "generic compiler panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to zero-initialized function",
+        _ => "unknown panic",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::abi::{encode, Token};
+
+    #[test]
+    fn test_decode_revert_reason() {
+        let mut data = ERROR_SELECTOR.to_vec();
+        data.extend(encode(&[Token::String("insufficient balance".to_string())]));
+        assert_eq!(decode_revert_reason(&data, None), Some("insufficient balance".to_string()));
+
+        let mut data = PANIC_SELECTOR.to_vec();
+        data.extend(encode(&[Token::Uint(U256::from(0x11))]));
+        assert_eq!(
+            decode_revert_reason(&data, None),
+            Some("Panic(0x11): arithmetic overflow or underflow".to_string())
+        );
+
+        let decoder =
+            FunctionDecoder::new(vec!["error InsufficientBalance(uint256 available)".to_string()])
+                .unwrap();
+        let mut data = prefix_hex::decode::<Vec<u8>>("0x92665351").unwrap();
+        data.extend(encode(&[Token::Uint(U256::from(7))]));
+        assert_eq!(
+            decode_revert_reason(&data, Some(&decoder)),
+            Some("InsufficientBalance(7)".to_string())
+        );
+        assert_eq!(decode_revert_reason(&data, None), Some("unknown error 0x92665351".to_string()));
+        assert_eq!(decode_revert_reason(&[], None), None);
+    }
+}
@@ -0,0 +1,243 @@
+use ethers::prelude::*;
+use ethers_core::utils::keccak256;
+use std::collections::{BTreeSet, HashMap};
+
+/// local database of text signatures, for labeling selectors and topics without network lookups
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SignatureDatabase {
+    /// text signatures by 4 byte function selector
+    pub functions: HashMap<[u8; 4], BTreeSet<String>>,
+    /// text signatures by event topic0
+    pub events: HashMap<H256, BTreeSet<String>>,
+}
+
+/// whether text signatures are of functions or of events
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureKind {
+    /// function signatures, labeling call data by selector
+    Function,
+    /// event signatures, labeling logs by topic0
+    Event,
+}
+
+impl SignatureKind {
+    /// kind named by a table or file, ex: `event_signatures`, functions unless it mentions events
+    pub fn from_name(name: &str) -> Self {
+        if name.to_lowercase().contains("event") {
+            SignatureKind::Event
+        } else {
+            SignatureKind::Function
+        }
+    }
+}
+
+/// text signature matching a selector or topic, and whether it was the only candidate
+pub type SignatureMatch = (Option<String>, Option<bool>);
+
+impl SignatureDatabase {
+    /// load signatures from a csv or sqlite dump, such as those of 4byte.directory or openchain
+    ///
+    /// any field that looks like a text signature, ex: `transfer(address,uint256)`, is used, and
+    /// its selector and topic are computed from the signature itself, so hash columns are ignored.
+    /// if `kind` is None, it is taken from each sqlite table name or from the csv file name
+    pub fn load_file(&mut self, path: &str, kind: Option<SignatureKind>) -> Result<(), String> {
+        let mut magic = [0u8; 16];
+        let is_sqlite = std::fs::File::open(path)
+            .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut magic))
+            .is_ok() &&
+            &magic == SQLITE_MAGIC;
+        if is_sqlite {
+            let tables = sqlite_signatures(path)
+                .map_err(|e| format!("invalid sqlite database {}: {}", path, e))?;
+            for (table, texts) in tables.into_iter() {
+                let kind = kind.unwrap_or_else(|| SignatureKind::from_name(&table));
+                self.add_signatures(kind, texts.iter().filter_map(|x| parse_signature(x)));
+            }
+        } else {
+            let contents = std::fs::read_to_string(path)
+                .map_err(|e| format!("could not read signature database {}: {}", path, e))?;
+            let file_name = std::path::Path::new(path).file_name().unwrap_or_default();
+            let kind =
+                kind.unwrap_or_else(|| SignatureKind::from_name(&file_name.to_string_lossy()));
+            self.add_signatures(kind, contents.lines().filter_map(parse_signature));
+        }
+        Ok(())
+    }
+
+    /// create database from text signatures of a single kind
+    pub fn from_signatures<S: AsRef<str>>(
+        kind: SignatureKind,
+        signatures: impl Iterator<Item = S>,
+    ) -> Self {
+        let mut database = Self::default();
+        database.add_signatures(kind, signatures);
+        database
+    }
+
+    /// add text signatures of a single kind
+    pub fn add_signatures<S: AsRef<str>>(
+        &mut self,
+        kind: SignatureKind,
+        signatures: impl Iterator<Item = S>,
+    ) {
+        for signature in signatures {
+            let signature = signature.as_ref();
+            let hash = keccak256(signature.as_bytes());
+            match kind {
+                SignatureKind::Function => {
+                    let mut selector = [0u8; 4];
+                    selector.copy_from_slice(&hash[..4]);
+                    self.functions.entry(selector).or_default().insert(signature.to_string());
+                }
+                SignatureKind::Event => {
+                    self.events.entry(H256(hash)).or_default().insert(signature.to_string());
+                }
+            }
+        }
+    }
+
+    /// label call data by its selector
+    pub fn function_signature(&self, input: &[u8]) -> SignatureMatch {
+        if input.len() < 4 {
+            return (None, None)
+        }
+        let mut selector = [0u8; 4];
+        selector.copy_from_slice(&input[..4]);
+        to_match(self.functions.get(&selector))
+    }
+
+    /// label a log by its topic0
+    pub fn event_signature(&self, topic0: Option<&H256>) -> SignatureMatch {
+        to_match(topic0.and_then(|topic0| self.events.get(topic0)))
+    }
+}
+
+/// candidates are joined with `|` when a selector has several signatures
+fn to_match(candidates: Option<&BTreeSet<String>>) -> SignatureMatch {
+    match candidates {
+        Some(candidates) => {
+            let signature = candidates.iter().cloned().collect::<Vec<_>>().join("|");
+            (Some(signature), Some(candidates.len() == 1))
+        }
+        None => (None, None),
+    }
+}
+
+/// find a text signature within a line of csv
+fn parse_signature(line: &str) -> Option<String> {
+    let start = line.find('(')?;
+    let end = line.rfind(')')?;
+    let name_start = line[..start]
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+        .map(|x| x + 1)
+        .unwrap_or(0);
+    if name_start == start || end < start {
+        return None
+    }
+    let signature: String = line[name_start..=end].chars().filter(|c| !c.is_whitespace()).collect();
+    Some(signature)
+}
+
+const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+
+/// text values of the signature columns of every table, ex: `text_signature` of 4byte dumps
+///
+/// reading goes through sqlite itself, so deleted rows are skipped and wal content is included
+fn sqlite_signatures(path: &str) -> rusqlite::Result<Vec<(String, Vec<String>)>> {
+    let connection =
+        rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let tables: Vec<String> = connection
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table'")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut output = Vec::new();
+    for table in tables.into_iter() {
+        let table_sql = quote_identifier(&table);
+        let columns: Vec<String> = connection
+            .prepare(&format!("PRAGMA table_info({})", table_sql))?
+            .query_map([], |row| row.get(1))?
+            .collect::<rusqlite::Result<_>>()?;
+        let mut texts = Vec::new();
+        for column in columns.iter().filter(|x| x.to_lowercase().contains("signature")) {
+            let sql = format!("SELECT {} FROM {}", quote_identifier(column), table_sql);
+            let mut statement = connection.prepare(&sql)?;
+            let values = statement.query_map([], |row| row.get::<_, rusqlite::types::Value>(0))?;
+            for value in values {
+                if let rusqlite::types::Value::Text(text) = value? {
+                    texts.push(text)
+                }
+            }
+        }
+        output.push((table, texts));
+    }
+    Ok(output)
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_database() {
+        let lines = [
+            "id,created_at,text_signature,hex_signature,bytes_signature",
+            "1,2018-01-01,\"transfer(address,uint256)\",0xa9059cbb,",
+            "0xa9059cbb,many_msg_babbage(bytes1)",
+        ];
+        let mut database = SignatureDatabase::from_signatures(
+            SignatureKind::Function,
+            lines.iter().filter_map(|x| parse_signature(x)),
+        );
+        database.add_signatures(SignatureKind::Event, ["Transfer(address,address,uint256)"].iter());
+
+        let input = prefix_hex::decode::<Vec<u8>>("0xa9059cbb").unwrap();
+        assert_eq!(
+            database.function_signature(&input),
+            (Some("many_msg_babbage(bytes1)|transfer(address,uint256)".to_string()), Some(false))
+        );
+
+        let topic0 = H256(keccak256("Transfer(address,address,uint256)"));
+        assert_eq!(
+            database.event_signature(Some(&topic0)),
+            (Some("Transfer(address,address,uint256)".to_string()), Some(true))
+        );
+        assert_eq!(database.event_signature(None), (None, None));
+        // function signatures do not label topics, and event signatures do not label calls
+        let topic0 = H256(keccak256("transfer(address,uint256)"));
+        assert_eq!(database.event_signature(Some(&topic0)), (None, None));
+        assert_eq!(database.functions.len(), 1);
+    }
+
+    #[test]
+    fn test_sqlite_signatures() {
+        let path = std::env::temp_dir().join(format!("cryo_signatures_{}.db", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+        let connection = rusqlite::Connection::open(&path).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE function_signatures (id INTEGER, text_signature TEXT, hex TEXT);
+                CREATE TABLE event_signatures (id INTEGER, text_signature TEXT);
+                INSERT INTO function_signatures VALUES
+                    (1, 'transfer(address,uint256)', '0xa9059cbb'),
+                    (2, 'deleted(uint256)', NULL);
+                INSERT INTO event_signatures VALUES (1, 'Transfer(address,address,uint256)');
+                DELETE FROM function_signatures WHERE id = 2;",
+            )
+            .unwrap();
+        drop(connection);
+
+        let mut database = SignatureDatabase::default();
+        database.load_file(&path, None).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let functions: Vec<_> = database.functions.values().flatten().cloned().collect();
+        let events: Vec<_> = database.events.values().flatten().cloned().collect();
+        assert_eq!(functions, vec!["transfer(address,uint256)".to_string()]);
+        assert_eq!(events, vec!["Transfer(address,address,uint256)".to_string()]);
+    }
+}
@@ -0,0 +1,755 @@
+use ethers::prelude::*;
+use ethers_core::utils::keccak256;
+use serde::Deserialize;
+use std::{collections::HashMap, sync::OnceLock};
+
+/// columns derived from storage layouts
+pub const STORAGE_COLUMNS: [&str; 6] = [
+    "variable",
+    "mapping_keys",
+    "value_type",
+    "decoded_value",
+    "decoded_from_value",
+    "decoded_to_value",
+];
+
+/// mappings nested deeper than this are not enumerated when matching keys
+const MAX_MAPPING_DEPTH: usize = 2;
+
+/// static arrays longer than this only have their first elements labeled
+const MAX_STATIC_ELEMENTS: usize = 256;
+
+/// slots this far past the start of a dynamic array or long bytes are not matched to it
+const MAX_REGION_SLOTS: u64 = 1 << 32;
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+struct StorageVariable {
+    label: String,
+    #[serde(default)]
+    offset: usize,
+    slot: String,
+    #[serde(rename = "type")]
+    type_id: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StorageType {
+    encoding: String,
+    label: String,
+    number_of_bytes: String,
+    key: Option<String>,
+    value: Option<String>,
+    base: Option<String>,
+    members: Option<Vec<StorageVariable>>,
+}
+
+/// storage layout of a contract, as output by solc with `storageLayout`
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct StorageLayout {
+    storage: Vec<StorageVariable>,
+    #[serde(default)]
+    types: Option<HashMap<String, StorageType>>,
+}
+
+/// a variable stored in a slot
+#[derive(Clone, Debug, PartialEq)]
+struct SlotEntry {
+    path: String,
+    keys: Vec<String>,
+    type_id: String,
+    offset: usize,
+}
+
+/// slots of a dynamic array or long bytes value, matched by distance from their start
+#[derive(Clone, Debug, PartialEq)]
+struct Region {
+    start: U256,
+    path: String,
+    keys: Vec<String>,
+    type_id: String,
+}
+
+/// slots of a layout, with mapping entries enumerated for a set of keys
+#[derive(Clone, Debug, Default, PartialEq)]
+struct SlotEntries {
+    entries: HashMap<H256, Vec<SlotEntry>>,
+    regions: Vec<Region>,
+}
+
+/// keys used to enumerate mapping entries
+///
+/// unless `all` is set, only entries whose path uses at least one of the new keys are indexed,
+/// so that an index of the old keys can be extended without enumerating it again
+struct KeySet<'k> {
+    old: &'k [H256],
+    new: &'k [H256],
+    all: bool,
+}
+
+/// slots of a layout that can be labeled with a given set of known mapping keys
+pub struct SlotIndex<'a> {
+    layout: &'a StorageLayout,
+    base: Option<&'a SlotEntries>,
+    extra: SlotEntries,
+}
+
+/// decoded columns of a slot, multiple packed variables are joined with `|`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DecodedSlot {
+    /// variable path, ex: `balances[0x..]` or `config.owner`
+    pub variable: Option<String>,
+    /// mapping keys recovered from the slot, joined with `,`
+    pub mapping_keys: Option<String>,
+    /// solidity type of the variable
+    pub value_type: Option<String>,
+    /// decoded value of each given slot value
+    pub values: Vec<Option<String>>,
+}
+
+impl StorageLayout {
+    /// parse a storage layout from json, either the layout itself or an artifact containing it
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| format!("could not parse layout: {}", e))?;
+        let value = match value.get("storageLayout") {
+            Some(layout) => layout.clone(),
+            None => value,
+        };
+        serde_json::from_value(value).map_err(|e| format!("invalid storage layout: {}", e))
+    }
+
+    fn get_type(&self, type_id: &str) -> Option<&StorageType> {
+        self.types.as_ref().and_then(|types| types.get(type_id))
+    }
+
+    fn type_label(&self, type_id: &str) -> String {
+        match self.get_type(type_id) {
+            Some(t) => t.label.clone(),
+            None => type_id.trim_start_matches("t_").to_string(),
+        }
+    }
+
+    fn type_size(&self, type_id: &str) -> usize {
+        self.get_type(type_id).and_then(|t| t.number_of_bytes.parse().ok()).unwrap_or(32)
+    }
+
+    fn variable(&self, label: &str) -> Option<&StorageVariable> {
+        self.storage.iter().find(|x| x.label == label)
+    }
+
+    /// index the slots of all variables, enumerating mapping entries for the known keys
+    pub fn slot_index(&self, known_keys: &[H256]) -> SlotIndex<'_> {
+        let keys = KeySet { old: &[], new: known_keys, all: true };
+        SlotIndex { layout: self, base: None, extra: self.index_entries(&keys) }
+    }
+
+    fn index_entries(&self, keys: &KeySet<'_>) -> SlotEntries {
+        let mut index = SlotEntries::default();
+        if !keys.all && keys.new.is_empty() {
+            return index
+        }
+        for variable in self.storage.iter() {
+            if let Ok(slot) = U256::from_dec_str(&variable.slot) {
+                let path = variable.label.clone();
+                let position = (slot, variable.offset);
+                self.add_entries(
+                    &mut index,
+                    keys,
+                    &variable.type_id,
+                    position,
+                    path,
+                    &[],
+                    0,
+                    false,
+                );
+            }
+        }
+        index
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add_entries(
+        &self,
+        index: &mut SlotEntries,
+        known_keys: &KeySet<'_>,
+        type_id: &str,
+        position: (U256, usize),
+        path: String,
+        keys: &[String],
+        depth: usize,
+        uses_new_key: bool,
+    ) {
+        let included = known_keys.all || uses_new_key;
+        if !included && !self.has_mapping(type_id) {
+            return
+        }
+        let (slot, offset) = position;
+        let entry = SlotEntry { path, keys: keys.to_vec(), type_id: type_id.to_string(), offset };
+        let t = match self.get_type(type_id) {
+            Some(t) => t,
+            None => {
+                index.entries.entry(u256_to_h256(slot)).or_default().push(entry);
+                return
+            }
+        };
+        match (t.encoding.as_str(), &t.members, &t.key, &t.value, &t.base) {
+            ("mapping", _, Some(key_type), Some(value_type), _) => {
+                if depth >= MAX_MAPPING_DEPTH {
+                    return
+                }
+                let old_keys = known_keys.old.iter().map(|key| (key, false));
+                let new_keys = known_keys.new.iter().map(|key| (key, true));
+                for (key, is_new) in old_keys.chain(new_keys) {
+                    let uses_new_key = uses_new_key || is_new;
+                    if !known_keys.all && !uses_new_key && !self.has_mapping(value_type) {
+                        continue
+                    }
+                    let display = match self.display_key(key_type, key) {
+                        Some(display) => display,
+                        None => continue,
+                    };
+                    let mut preimage = key.as_bytes().to_vec();
+                    preimage.extend(u256_to_h256(slot).as_bytes());
+                    let entry_slot = U256::from_big_endian(&keccak256(preimage));
+                    let path = format!("{}[{}]", entry.path, display);
+                    let mut keys = entry.keys.clone();
+                    keys.push(display);
+                    let position = (entry_slot, 0);
+                    self.add_entries(
+                        index,
+                        known_keys,
+                        value_type,
+                        position,
+                        path,
+                        &keys,
+                        depth + 1,
+                        uses_new_key,
+                    )
+                }
+            }
+            ("dynamic_array", _, _, _, _) | ("bytes", _, _, _, _) => {
+                if !included {
+                    return
+                }
+                let start = U256::from_big_endian(&keccak256(u256_to_h256(slot).as_bytes()));
+                index.regions.push(Region {
+                    start,
+                    path: entry.path.clone(),
+                    keys: entry.keys.clone(),
+                    type_id: type_id.to_string(),
+                });
+                index.entries.entry(u256_to_h256(slot)).or_default().push(entry);
+            }
+            ("inplace", Some(members), _, _, _) => {
+                for member in members.iter() {
+                    if let Ok(member_slot) = U256::from_dec_str(&member.slot) {
+                        let path = format!("{}.{}", entry.path, member.label);
+                        let position = (slot + member_slot, member.offset);
+                        self.add_entries(
+                            index,
+                            known_keys,
+                            &member.type_id,
+                            position,
+                            path,
+                            keys,
+                            depth,
+                            uses_new_key,
+                        );
+                    }
+                }
+            }
+            ("inplace", None, _, _, Some(base)) => {
+                let length = static_array_length(type_id).unwrap_or(0).min(MAX_STATIC_ELEMENTS);
+                let size = self.type_size(base);
+                for i in 0..length {
+                    let position = element_position(slot, i, size);
+                    let path = format!("{}[{}]", entry.path, i);
+                    self.add_entries(
+                        index,
+                        known_keys,
+                        base,
+                        position,
+                        path,
+                        keys,
+                        depth,
+                        uses_new_key,
+                    );
+                }
+            }
+            _ if included => index.entries.entry(u256_to_h256(slot)).or_default().push(entry),
+            _ => {}
+        }
+    }
+
+    /// whether a type has mapping entries that are enumerated from keys
+    fn has_mapping(&self, type_id: &str) -> bool {
+        match self.get_type(type_id) {
+            Some(t) if t.encoding == "mapping" => true,
+            Some(t) if t.encoding == "inplace" => {
+                t.members.iter().flatten().any(|member| self.has_mapping(&member.type_id)) ||
+                    t.base.as_ref().map(|base| self.has_mapping(base)).unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+
+    /// representation of a mapping key, None if the key cannot be of the key type
+    fn display_key(&self, key_type: &str, key: &H256) -> Option<String> {
+        let label = self.type_label(key_type);
+        let bytes = key.as_bytes();
+        if label == "address" || label.starts_with("contract ") {
+            if bytes[..12].iter().any(|b| *b != 0) {
+                return None
+            }
+        } else if label == "string" || label == "bytes" {
+            // keys of dynamic types are hashed unpadded, so 32 byte words cannot match them
+            return None
+        }
+        self.decode_value(key_type, key, 0)
+    }
+
+    /// decode the value of a variable from a slot word
+    fn decode_value(&self, type_id: &str, word: &H256, offset: usize) -> Option<String> {
+        let label = self.type_label(type_id);
+        let encoding = self.get_type(type_id).map(|t| t.encoding.as_str()).unwrap_or("inplace");
+        let bytes = word.as_bytes();
+        let size = self.type_size(type_id).min(32);
+        let end = 32usize.saturating_sub(offset);
+        let data = &bytes[end.saturating_sub(size)..end];
+        let value = match encoding {
+            "mapping" => return None,
+            "dynamic_array" => U256::from_big_endian(bytes).to_string(),
+            "bytes" => {
+                if bytes[31] & 1 == 0 {
+                    let content = &bytes[..((bytes[31] / 2) as usize).min(31)];
+                    if label == "string" {
+                        String::from_utf8_lossy(content).to_string()
+                    } else {
+                        prefix_hex::encode(content.to_vec())
+                    }
+                } else {
+                    let length = (U256::from_big_endian(bytes) - 1) / 2;
+                    format!("{} bytes", length)
+                }
+            }
+            _ if label.starts_with("uint") || label.starts_with("enum ") => {
+                U256::from_big_endian(data).to_string()
+            }
+            _ if label.starts_with("int") => {
+                let fill =
+                    if data.first().map(|b| b & 0x80 != 0).unwrap_or(false) { 0xff } else { 0 };
+                let mut extended = vec![fill; 32 - data.len()];
+                extended.extend(data);
+                I256::from_raw(U256::from_big_endian(&extended)).to_string()
+            }
+            _ if label.starts_with("address") || label.starts_with("contract ") => {
+                format!("{:?}", H160::from_slice(&data[data.len().saturating_sub(20)..]))
+            }
+            _ if label == "bool" => (data.last().copied().unwrap_or(0) != 0).to_string(),
+            _ => prefix_hex::encode(data.to_vec()),
+        };
+        Some(value)
+    }
+
+    /// slot of a mapping entry, ex: `balances[0x..]`, `allowance[0x..][0x..]`, or `3[0x..]`
+    ///
+    /// returns the slot and the 32 byte words of the keys, which can be used as known keys
+    pub fn mapping_slot(
+        layouts: &[&StorageLayout],
+        expression: &str,
+    ) -> Result<(H256, Vec<H256>), String> {
+        let invalid = || format!("invalid mapping slot {}, expected name[key]", expression);
+        let (name, rest) = expression.split_once('[').ok_or_else(invalid)?;
+        let rest = rest.strip_suffix(']').ok_or_else(invalid)?;
+        let raw_keys: Vec<&str> = rest.split("][").collect();
+
+        // find base slot, and the type of the mapping if it is in a layout
+        let (mut slot, mut typed) = match parse_u256(name) {
+            Some(slot) => (slot, None),
+            None => {
+                let matches: Vec<_> = layouts
+                    .iter()
+                    .filter_map(|layout| layout.variable(name).map(|x| (layout, x)))
+                    .collect();
+                let (layout, variable) =
+                    matches.first().ok_or(format!("no storage variable named {}", name))?;
+                if matches.iter().any(|(_, x)| x.slot != variable.slot) {
+                    return Err(format!("storage variable {} has different slots in layouts", name))
+                }
+                let slot = U256::from_dec_str(&variable.slot).map_err(|_| invalid())?;
+                (slot, Some((*layout, variable.type_id.clone())))
+            }
+        };
+
+        let mut key_words = Vec::new();
+        for raw_key in raw_keys.into_iter() {
+            let (encoded, next) = match &typed {
+                Some((layout, type_id)) => {
+                    let t = layout.get_type(type_id).filter(|t| t.encoding == "mapping");
+                    let (key_type, value_type) = match t.map(|t| (&t.key, &t.value)) {
+                        Some((Some(key_type), Some(value_type))) => (key_type, value_type),
+                        _ => return Err(format!("{} is not a mapping", name)),
+                    };
+                    let label = layout.type_label(key_type);
+                    (encode_key(Some(&label), raw_key)?, Some((*layout, value_type.clone())))
+                }
+                None => (encode_key(None, raw_key)?, None),
+            };
+            if encoded.len() == 32 {
+                key_words.push(H256::from_slice(&encoded));
+            }
+            let mut preimage = encoded;
+            preimage.extend(u256_to_h256(slot).as_bytes());
+            slot = U256::from_big_endian(&keccak256(preimage));
+            typed = next;
+        }
+        Ok((u256_to_h256(slot), key_words))
+    }
+}
+
+impl SlotEntries {
+    /// entries of slots whose location was enumerated
+    fn exact(&self, slot: &H256) -> Option<&Vec<SlotEntry>> {
+        self.entries.get(slot)
+    }
+
+    /// entries of a slot within a dynamic array or long bytes value
+    fn in_region(&self, layout: &StorageLayout, slot: &H256) -> Vec<SlotEntry> {
+        let slot = U256::from_big_endian(slot.as_bytes());
+        for region in self.regions.iter() {
+            if slot < region.start || slot - region.start >= U256::from(MAX_REGION_SLOTS) {
+                continue
+            }
+            let delta = (slot - region.start).as_u64() as usize;
+            let t = layout.get_type(&region.type_id);
+            let entry = |path: String, type_id: &str, offset: usize| SlotEntry {
+                path,
+                keys: region.keys.clone(),
+                type_id: type_id.to_string(),
+                offset,
+            };
+            match t.and_then(|t| t.base.as_ref()) {
+                // elements of a dynamic array
+                Some(base) => {
+                    let size = layout.type_size(base);
+                    if size >= 32 {
+                        let slots_per_element = size.div_ceil(32);
+                        let i = delta / slots_per_element;
+                        let inner = delta % slots_per_element;
+                        let path = format!("{}[{}]", region.path, i);
+                        let members = layout.get_type(base).and_then(|x| x.members.as_ref());
+                        match members {
+                            Some(members) => {
+                                return members
+                                    .iter()
+                                    .filter(|m| m.slot == inner.to_string())
+                                    .map(|m| {
+                                        entry(format!("{}.{}", path, m.label), &m.type_id, m.offset)
+                                    })
+                                    .collect()
+                            }
+                            None if inner == 0 => return vec![entry(path, base, 0)],
+                            None => return vec![],
+                        }
+                    } else {
+                        let per_slot = 32 / size;
+                        return (0..per_slot)
+                            .map(|j| {
+                                let path = format!("{}[{}]", region.path, delta * per_slot + j);
+                                entry(path, base, j * size)
+                            })
+                            .collect()
+                    }
+                }
+                // data of long bytes or string
+                None => return vec![entry(region.path.clone(), "t_bytes32", 0)],
+            }
+        }
+        vec![]
+    }
+}
+
+impl SlotIndex<'_> {
+    fn label(&self, slot: &H256) -> Vec<SlotEntry> {
+        let indexes = || std::iter::once(&self.extra).chain(self.base);
+        if let Some(entries) = indexes().find_map(|index| index.exact(slot)) {
+            return entries.clone()
+        }
+        for index in indexes() {
+            let entries = index.in_region(self.layout, slot);
+            if !entries.is_empty() {
+                return entries
+            }
+        }
+        vec![]
+    }
+
+    /// decode a slot and its values
+    pub fn decode(&self, slot: &H256, values: &[H256]) -> DecodedSlot {
+        let entries = self.label(slot);
+        if entries.is_empty() {
+            return DecodedSlot { values: vec![None; values.len()], ..Default::default() }
+        }
+        let join = |items: Vec<String>| items.join("|");
+        let keys = &entries[0].keys;
+        DecodedSlot {
+            variable: Some(join(entries.iter().map(|x| x.path.clone()).collect())),
+            mapping_keys: if keys.is_empty() { None } else { Some(keys.join(",")) },
+            value_type: Some(join(
+                entries.iter().map(|x| self.layout.type_label(&x.type_id)).collect(),
+            )),
+            values: values
+                .iter()
+                .map(|value| {
+                    let decoded: Vec<String> = entries
+                        .iter()
+                        .filter_map(|x| self.layout.decode_value(&x.type_id, value, x.offset))
+                        .collect();
+                    if decoded.is_empty() {
+                        None
+                    } else {
+                        Some(join(decoded))
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// storage layouts of contracts, and known keys for recovering mapping entries
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StorageDecoder {
+    /// layouts by contract address
+    pub layouts: HashMap<H160, StorageLayout>,
+    /// layout for contracts without their own layout
+    pub default_layout: Option<StorageLayout>,
+    /// known mapping keys, as 32 byte words
+    pub keys: Vec<H256>,
+    /// index of each layout using the known keys, built on first use, by contract address or
+    /// None for the default layout
+    indexes: OnceLock<HashMap<Option<H160>, SlotEntries>>,
+}
+
+impl StorageDecoder {
+    /// add known mapping keys, hex keys are left padded to 32 bytes and decimal keys are uints
+    pub fn add_keys(&mut self, raw_keys: &[String]) -> Result<(), String> {
+        for raw_key in raw_keys.iter() {
+            let key = H256::from_slice(&encode_key(None, raw_key)?);
+            if !self.keys.contains(&key) {
+                self.keys.push(key)
+            }
+        }
+        self.indexes = OnceLock::new();
+        Ok(())
+    }
+
+    /// layout of a contract
+    pub fn layout(&self, address: &H160) -> Option<&StorageLayout> {
+        self.layouts.get(address).or(self.default_layout.as_ref())
+    }
+
+    /// all layouts of decoder
+    pub fn all_layouts(&self) -> Vec<&StorageLayout> {
+        self.default_layout.iter().chain(self.layouts.values()).collect()
+    }
+
+    /// index slots of a contract, using the known keys and any extra keys such as addresses
+    /// involved in the same transaction
+    ///
+    /// entries of the known keys are indexed once per layout, so only entries using extra keys
+    /// are enumerated for each call
+    pub fn slot_index(&self, address: &H160, extra_keys: &[H256]) -> Option<SlotIndex<'_>> {
+        let layout = self.layout(address)?;
+        let indexes = self.indexes.get_or_init(|| {
+            let keys = KeySet { old: &[], new: &self.keys, all: true };
+            let default = self.default_layout.iter().map(|layout| (None, layout));
+            let layouts = self.layouts.iter().map(|(address, layout)| (Some(*address), layout));
+            default.chain(layouts).map(|(k, layout)| (k, layout.index_entries(&keys))).collect()
+        });
+        let index_key = if self.layouts.contains_key(address) { Some(*address) } else { None };
+        let mut new_keys: Vec<H256> = Vec::new();
+        for key in extra_keys.iter() {
+            if !self.keys.contains(key) && !new_keys.contains(key) {
+                new_keys.push(*key)
+            }
+        }
+        let keys = KeySet { old: &self.keys, new: &new_keys, all: false };
+        Some(SlotIndex {
+            layout,
+            base: indexes.get(&index_key),
+            extra: layout.index_entries(&keys),
+        })
+    }
+}
+
+fn u256_to_h256(value: U256) -> H256 {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    H256(bytes)
+}
+
+fn parse_u256(raw: &str) -> Option<U256> {
+    if let Some(hex) = raw.strip_prefix("0x") {
+        U256::from_str_radix(hex, 16).ok()
+    } else {
+        U256::from_dec_str(raw).ok()
+    }
+}
+
+/// length of a static array type, ex: `t_array(t_uint256)3_storage`
+fn static_array_length(type_id: &str) -> Option<usize> {
+    let suffix = &type_id[type_id.rfind(')')? + 1..];
+    suffix.split('_').next()?.parse().ok()
+}
+
+/// slot and offset of a static array element
+fn element_position(slot: U256, i: usize, size: usize) -> (U256, usize) {
+    if size >= 32 {
+        (slot + U256::from(i * size.div_ceil(32)), 0)
+    } else {
+        let per_slot = 32 / size;
+        (slot + U256::from(i / per_slot), (i % per_slot) * size)
+    }
+}
+
+/// encode a mapping key for hashing, inferring the type from the key if not given
+fn encode_key(label: Option<&str>, raw: &str) -> Result<Vec<u8>, String> {
+    let invalid = || format!("invalid mapping key {}", raw);
+    let left_pad = |bytes: Vec<u8>| -> Result<Vec<u8>, String> {
+        if bytes.len() > 32 {
+            return Err(invalid())
+        }
+        let mut word = vec![0u8; 32 - bytes.len()];
+        word.extend(bytes);
+        Ok(word)
+    };
+    let word = |value: U256| u256_to_h256(value).as_bytes().to_vec();
+    match label {
+        Some("string") => Ok(raw.as_bytes().to_vec()),
+        Some("bytes") => prefix_hex::decode::<Vec<u8>>(raw).map_err(|_| invalid()),
+        Some("bool") => match raw {
+            "true" => Ok(word(U256::one())),
+            "false" => Ok(word(U256::zero())),
+            _ => Err(invalid()),
+        },
+        Some(label) if label.starts_with("bytes") => {
+            let mut bytes = prefix_hex::decode::<Vec<u8>>(raw).map_err(|_| invalid())?;
+            if bytes.len() > 32 {
+                return Err(invalid())
+            }
+            bytes.resize(32, 0);
+            Ok(bytes)
+        }
+        Some(label) if label.starts_with("int") => {
+            let value = match raw.strip_prefix('-') {
+                Some(raw) => -I256::from_raw(parse_u256(raw).ok_or_else(invalid)?),
+                None => I256::from_raw(parse_u256(raw).ok_or_else(invalid)?),
+            };
+            Ok(word(value.into_raw()))
+        }
+        Some(label) if label.starts_with("address") || label.starts_with("contract ") => {
+            left_pad(prefix_hex::decode::<Vec<u8>>(raw).map_err(|_| invalid())?)
+        }
+        _ => match raw.starts_with("0x") {
+            true => left_pad(prefix_hex::decode::<Vec<u8>>(raw).map_err(|_| invalid())?),
+            false => parse_u256(raw).map(word).ok_or_else(invalid),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUT: &str = r#"{
+        "storage": [
+            {"label": "owner", "offset": 0, "slot": "0", "type": "t_address"},
+            {"label": "paused", "offset": 20, "slot": "0", "type": "t_bool"},
+            {"label": "balances", "offset": 0, "slot": "1", "type": "t_mapping(t_address,t_uint256)"},
+            {"label": "holders", "offset": 0, "slot": "2", "type": "t_array(t_address)dyn_storage"}
+        ],
+        "types": {
+            "t_address": {"encoding": "inplace", "label": "address", "numberOfBytes": "20"},
+            "t_bool": {"encoding": "inplace", "label": "bool", "numberOfBytes": "1"},
+            "t_uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"},
+            "t_mapping(t_address,t_uint256)": {"encoding": "mapping", "key": "t_address", "label": "mapping(address => uint256)", "numberOfBytes": "32", "value": "t_uint256"},
+            "t_array(t_address)dyn_storage": {"encoding": "dynamic_array", "base": "t_address", "label": "address[]", "numberOfBytes": "32"}
+        }
+    }"#;
+
+    #[test]
+    fn test_slot_index() {
+        let layout = StorageLayout::from_json(LAYOUT).unwrap();
+        let holder = H160::from_low_u64_be(0xbeef);
+        let expression = format!("balances[{:?}]", holder);
+        let (slot, keys) = StorageLayout::mapping_slot(&[&layout], &expression).unwrap();
+        assert_eq!(keys, vec![H256::from(holder)]);
+        let (same_slot, _) = StorageLayout::mapping_slot(&[], &format!("1[{:?}]", holder)).unwrap();
+        assert_eq!(slot, same_slot);
+
+        let index = layout.slot_index(&keys);
+        let decoded = index.decode(&slot, &[u256_to_h256(U256::from(100))]);
+        assert_eq!(decoded.variable, Some(expression));
+        assert_eq!(decoded.mapping_keys, Some(format!("{:?}", holder)));
+        assert_eq!(decoded.values, vec![Some("100".to_string())]);
+
+        // packed variables share a slot
+        let mut word = [0u8; 32];
+        word[11] = 1;
+        word[12..].copy_from_slice(holder.as_bytes());
+        let decoded = index.decode(&H256::zero(), &[H256(word)]);
+        assert_eq!(decoded.variable, Some("owner|paused".to_string()));
+        assert_eq!(decoded.values, vec![Some(format!("{:?}|true", holder))]);
+
+        // elements of dynamic arrays are found by their distance from the array start
+        let start = U256::from_big_endian(&keccak256(u256_to_h256(U256::from(2)).as_bytes()));
+        let decoded = index.decode(&u256_to_h256(start + 3), &[]);
+        assert_eq!(decoded.variable, Some("holders[3]".to_string()));
+    }
+
+    #[test]
+    fn test_decoder_extra_keys() {
+        let layout = r#"{
+            "storage": [
+                {"label": "total", "offset": 0, "slot": "0", "type": "t_uint256"},
+                {"label": "allowance", "offset": 0, "slot": "1", "type": "t_mapping(t_address,t_mapping(t_address,t_uint256))"}
+            ],
+            "types": {
+                "t_address": {"encoding": "inplace", "label": "address", "numberOfBytes": "20"},
+                "t_uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"},
+                "t_mapping(t_address,t_uint256)": {"encoding": "mapping", "key": "t_address", "label": "mapping(address => uint256)", "numberOfBytes": "32", "value": "t_uint256"},
+                "t_mapping(t_address,t_mapping(t_address,t_uint256))": {"encoding": "mapping", "key": "t_address", "label": "mapping(address => mapping(address => uint256))", "numberOfBytes": "32", "value": "t_mapping(t_address,t_uint256)"}
+            }
+        }"#;
+        let known = H160::from_low_u64_be(1);
+        let extra = H160::from_low_u64_be(2);
+        let mut decoder = StorageDecoder {
+            default_layout: Some(StorageLayout::from_json(layout).unwrap()),
+            ..Default::default()
+        };
+        decoder.add_keys(&[format!("{:?}", known)]).unwrap();
+        let contract = H160::from_low_u64_be(3);
+        let variable = |owner: H160, spender: H160| {
+            let expression = format!("allowance[{:?}][{:?}]", owner, spender);
+            let layouts = decoder.all_layouts();
+            let (slot, _) = StorageLayout::mapping_slot(&layouts, &expression).unwrap();
+            let index = decoder.slot_index(&contract, &[H256::from(extra)]).unwrap();
+            (index.decode(&slot, &[]).variable, Some(expression))
+        };
+
+        // entries are found whether they use known keys, extra keys, or both
+        for (owner, spender) in [(known, known), (known, extra), (extra, known), (extra, extra)] {
+            let (decoded, expected) = variable(owner, spender);
+            assert_eq!(decoded, expected);
+        }
+
+        // the extra keys of one call are not kept for later calls
+        let index = decoder.slot_index(&contract, &[]).unwrap();
+        assert_eq!(index.extra, SlotEntries::default());
+        assert_eq!(index.decode(&H256::zero(), &[]).variable, Some("total".to_string()));
+        let expression = format!("allowance[{:?}][{:?}]", known, extra);
+        let (slot, _) = StorageLayout::mapping_slot(&decoder.all_layouts(), &expression).unwrap();
+        assert_eq!(index.decode(&slot, &[]).variable, None);
+    }
+}
@@ -63,12 +63,21 @@ impl FileOutput {
         query: &Query,
         partition: &Partition,
         datatype: Datatype,
+    ) -> Result<PathBuf, CollectError> {
+        self.get_labeled_path(datatype, &partition.label(&query.partitioned_by)?)
+    }
+
+    /// get output file path using an explicit label, e.g. a time window of streamed data
+    pub fn get_labeled_path(
+        &self,
+        datatype: Datatype,
+        label: &str,
     ) -> Result<PathBuf, CollectError> {
         let filename = format!(
             "{}__{}__{}.{}",
             self.prefix.clone(),
             datatype.name(),
-            partition.label(&query.partitioned_by)?,
+            label,
             self.format.as_str(),
         );
         let filename = std::path::Path::new(&filename).to_path_buf();
@@ -1,6 +1,5 @@
 /// type specifications for cryo_freeze crate
 
-
 /// type specifications for chunk types
 pub mod chunks;
 /// conversion operations
@@ -54,13 +53,18 @@ pub use chunks::{
     AddressChunk, BlockChunk, CallDataChunk, Chunk, ChunkData, ChunkStats, SlotChunk, Subchunk,
     TopicChunk, TransactionChunk,
 };
-pub use conversions::{bytes_to_u32, ToVecHex, ToVecU8};
+pub use conversions::{bytes_to_address, bytes_to_u256, bytes_to_u32, ToVecHex, ToVecU8};
 pub use dataframes::*;
 pub use datatypes::*;
 pub use files::{ColumnEncoding, FileFormat, FileOutput, SubDir};
 pub use queries::{Query, QueryLabels, TimeDimension};
+#[cfg(test)]
+pub(crate) use schemas::test_schema;
 pub use schemas::{ColumnType, SchemaFunctions, Schemas, Table, U256Type};
-pub use sources::{Fetcher, RateLimiter, Source, SourceLabels};
+pub use sources::{
+    CallBundleResponse, CallBundleResult, Fetcher, OtsContractCreator, OtsTransactionsPage,
+    RateLimiter, Source, SourceLabels,
+};
 // pub(crate) use summaries::FreezeSummaryAgg;
 // pub use summaries::{FreezeChunkSummary, FreezeSummary};
 pub use summaries::{print_all_datasets, print_dataset_info, FreezeSummary};
@@ -16,6 +16,8 @@ pub enum Dim {
     Address,
     /// Contract dimension
     Contract,
+    /// FromAddress dimension
+    FromAddress,
     /// ToAddress dimension
     ToAddress,
     /// Slot dimension
@@ -39,6 +41,7 @@ impl Dim {
             Dim::CallData,
             Dim::Address,
             Dim::Contract,
+            Dim::FromAddress,
             Dim::ToAddress,
             Dim::Slot,
             Dim::Topic0,
@@ -56,6 +59,7 @@ impl Dim {
             Dim::CallData => "call_datas",
             Dim::Address => "addresses",
             Dim::Contract => "contracts",
+            Dim::FromAddress => "from_addresses",
             Dim::ToAddress => "to_addresses",
             Dim::Slot => "slots",
             Dim::Topic0 => "topic0s",
@@ -77,6 +81,7 @@ impl std::str::FromStr for Dim {
             "call_data" => Dim::CallData,
             "address" => Dim::Address,
             "contract" => Dim::Contract,
+            "from_address" => Dim::FromAddress,
             "to_address" => Dim::ToAddress,
             "slot" => Dim::Slot,
             "topic0" => Dim::Topic0,
@@ -97,6 +102,7 @@ impl std::fmt::Display for Dim {
             Dim::CallData => "call_data",
             Dim::Address => "address",
             Dim::Contract => "contract",
+            Dim::FromAddress => "from_address",
             Dim::ToAddress => "to_address",
             Dim::Slot => "slot",
             Dim::Topic0 => "topic0",
@@ -123,6 +129,8 @@ pub struct Partition {
     pub addresses: Option<Vec<AddressChunk>>,
     /// contracts
     pub contracts: Option<Vec<AddressChunk>>,
+    /// from addresses
+    pub from_addresses: Option<Vec<AddressChunk>>,
     /// to addresses
     pub to_addresses: Option<Vec<AddressChunk>>,
     /// slots
@@ -255,6 +263,7 @@ impl Partition {
                     Dim::CallData => chunks_to_name(&self.call_datas)?,
                     Dim::Address => chunks_to_name(&self.addresses)?,
                     Dim::Contract => chunks_to_name(&self.contracts)?,
+                    Dim::FromAddress => chunks_to_name(&self.from_addresses)?,
                     Dim::ToAddress => chunks_to_name(&self.to_addresses)?,
                     Dim::Slot => chunks_to_name(&self.slots)?,
                     Dim::Topic0 => chunks_to_name(&self.topic0s)?,
@@ -282,6 +291,7 @@ impl Partition {
                 Dim::TransactionHash => partition!(outputs, transactions)?,
                 Dim::Address => partition!(outputs, addresses)?,
                 Dim::Contract => partition!(outputs, contracts)?,
+                Dim::FromAddress => partition!(outputs, from_addresses)?,
                 Dim::ToAddress => partition!(outputs, to_addresses)?,
                 Dim::CallData => partition!(outputs, call_datas)?,
                 Dim::Slot => partition!(outputs, slots)?,
@@ -309,6 +319,7 @@ impl Partition {
                 Dim::TransactionHash => label_partition!(outputs, dim_labels, transactions)?,
                 Dim::Address => label_partition!(outputs, dim_labels, addresses)?,
                 Dim::Contract => label_partition!(outputs, dim_labels, contracts)?,
+                Dim::FromAddress => label_partition!(outputs, dim_labels, from_addresses)?,
                 Dim::ToAddress => label_partition!(outputs, dim_labels, to_addresses)?,
                 Dim::CallData => label_partition!(outputs, dim_labels, call_datas)?,
                 Dim::Slot => label_partition!(outputs, dim_labels, slots)?,
@@ -342,6 +353,7 @@ impl Partition {
                 }
                 Dim::Address => parametrize!(outputs, new, self.addresses, address),
                 Dim::Contract => parametrize!(outputs, new, self.contracts, contract),
+                Dim::FromAddress => parametrize!(outputs, new, self.from_addresses, from_address),
                 Dim::ToAddress => parametrize!(outputs, new, self.to_addresses, to_address),
                 Dim::CallData => parametrize!(outputs, new, self.call_datas, call_data),
                 Dim::Slot => parametrize!(outputs, new, self.slots, slot),
@@ -402,6 +414,9 @@ impl Partition {
         if self.contracts.is_some() {
             dims.push(Dim::Contract)
         };
+        if self.from_addresses.is_some() {
+            dims.push(Dim::FromAddress)
+        };
         if self.to_addresses.is_some() {
             dims.push(Dim::ToAddress)
         };
@@ -433,6 +448,7 @@ impl Partition {
             Dim::TransactionHash => self.transactions.as_ref().map(|x| x.len()).unwrap_or(0),
             Dim::Address => self.addresses.as_ref().map(|x| x.len()).unwrap_or(0),
             Dim::Contract => self.contracts.as_ref().map(|x| x.len()).unwrap_or(0),
+            Dim::FromAddress => self.from_addresses.as_ref().map(|x| x.len()).unwrap_or(0),
             Dim::ToAddress => self.to_addresses.as_ref().map(|x| x.len()).unwrap_or(0),
             Dim::CallData => self.call_datas.as_ref().map(|x| x.len()).unwrap_or(0),
             Dim::Slot => self.slots.as_ref().map(|x| x.len()).unwrap_or(0),
@@ -452,6 +468,7 @@ impl Partition {
             call_datas: chunk.call_datas.map(|c| c.stats()),
             addresses: chunk.addresses.map(|c| c.stats()),
             contracts: chunk.contracts.map(|c| c.stats()),
+            from_addresses: chunk.from_addresses.map(|c| c.stats()),
             to_addresses: chunk.to_addresses.map(|c| c.stats()),
             slots: chunk.slots.map(|c| c.stats()),
             topic0s: chunk.topic0s.map(|c| c.stats()),
@@ -483,6 +500,8 @@ pub struct PartitionStats {
     pub addresses: Option<ChunkStats<Vec<u8>>>,
     /// contracts stats
     pub contracts: Option<ChunkStats<Vec<u8>>>,
+    /// from_addresses stats
+    pub from_addresses: Option<ChunkStats<Vec<u8>>>,
     /// to_addresses stats
     pub to_addresses: Option<ChunkStats<Vec<u8>>>,
     /// slots stats
@@ -517,6 +536,7 @@ impl PartitionStats {
             call_datas: fold(self.call_datas, other.call_datas),
             addresses: fold(self.addresses, other.addresses),
             contracts: fold(self.contracts, other.contracts),
+            from_addresses: fold(self.from_addresses, other.from_addresses),
             to_addresses: fold(self.to_addresses, other.to_addresses),
             slots: fold(self.slots, other.slots),
             topic0s: fold(self.topic0s, other.topic0s),
@@ -539,6 +559,8 @@ pub struct PartitionLabels {
     pub address_labels: Option<Vec<Option<String>>>,
     /// contract labels
     pub contract_labels: Option<Vec<Option<String>>>,
+    /// from address labels
+    pub from_address_labels: Option<Vec<Option<String>>>,
     /// to address labels
     pub to_address_labels: Option<Vec<Option<String>>>,
     /// slot labels
@@ -561,6 +583,7 @@ impl PartitionLabels {
             Dim::CallData => self.call_data_labels.clone(),
             Dim::Address => self.address_labels.clone(),
             Dim::Contract => self.contract_labels.clone(),
+            Dim::FromAddress => self.from_address_labels.clone(),
             Dim::ToAddress => self.to_address_labels.clone(),
             Dim::Slot => self.slot_labels.clone(),
             Dim::Topic0 => self.topic0_labels.clone(),
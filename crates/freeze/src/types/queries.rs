@@ -1,4 +1,8 @@
-use crate::{CollectError, Datatype, Dim, MetaDatatype, Partition, Table};
+use crate::{
+    BundleTransaction, CallOverrides, CollectError, CustomTracer, Datatype, Dim, MetaDatatype,
+    Partition, PriceSource, Table, TransferFiles,
+};
+use ethers::prelude::U256;
 use std::collections::{HashMap, HashSet};
 
 /// Query
@@ -16,6 +20,24 @@ pub struct Query {
     pub partitioned_by: Vec<Dim>,
     /// Exclude failed
     pub exclude_failed: bool,
+    /// Pools and oracles to use for prices
+    pub price_sources: Option<Vec<PriceSource>>,
+    /// Token ids to use for erc721 owners
+    pub token_ids: Option<Vec<U256>>,
+    /// Transfer files to replay instead of calling the rpc
+    pub transfer_files: Option<TransferFiles>,
+    /// Number of replayed balances to verify against the rpc
+    pub spot_check: Option<u64>,
+    /// Use the flatCallTracer for geth traces
+    pub flat_call_tracer: bool,
+    /// Custom tracer for geth custom traces
+    pub custom_tracer: Option<CustomTracer>,
+    /// State and block overrides for eth_calls and trace_calls
+    pub call_overrides: Option<CallOverrides>,
+    /// Ordered bundles of transactions for bundle simulations
+    pub bundles: Option<Vec<Vec<BundleTransaction>>>,
+    /// Simulate bundles with eth_callBundle instead of trace_callMany
+    pub call_bundle: bool,
     /// Labels (these are non-functional)
     pub labels: QueryLabels,
 }
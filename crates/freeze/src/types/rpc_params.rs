@@ -16,6 +16,8 @@ pub struct Params {
     pub address: Option<Vec<u8>>,
     /// contract
     pub contract: Option<Vec<u8>>,
+    /// from address
+    pub from_address: Option<Vec<u8>>,
     /// to address
     pub to_address: Option<Vec<u8>>,
     /// slot
@@ -107,4 +109,37 @@ impl Params {
         };
         Ok(filter)
     }
+
+    /// trace filter
+    pub fn ethers_trace_filter(&self) -> Result<TraceFilter, CollectError> {
+        let (start, end) = self.block_range()?;
+        let mut filter = TraceFilter::default().from_block(start).to_block(end);
+        if let Some(from_address) = &self.from_address {
+            filter = filter.from_address(vec![H160::from_slice(from_address)]);
+        }
+        if let Some(to_address) = &self.to_address {
+            filter = filter.to_address(vec![H160::from_slice(to_address)]);
+        }
+        Ok(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ethers_trace_filter() {
+        let params = Params {
+            block_range: Some((10, 20)),
+            from_address: Some(vec![1; 20]),
+            to_address: Some(vec![2; 20]),
+            ..Default::default()
+        };
+        let filter = serde_json::to_value(params.ethers_trace_filter().unwrap()).unwrap();
+        assert_eq!(filter["fromBlock"], "0xa");
+        assert_eq!(filter["toBlock"], "0x14");
+        assert_eq!(filter["fromAddress"], serde_json::json!([H160::from_slice(&[1; 20])]));
+        assert_eq!(filter["toAddress"], serde_json::json!([H160::from_slice(&[2; 20])]));
+    }
 }
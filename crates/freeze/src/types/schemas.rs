@@ -1,7 +1,13 @@
 /// types and functions related to schemas
-use std::collections::{HashMap, HashSet};
-
-use crate::{err, CollectError, ColumnEncoding, Datatype, LogDecoder};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::{
+    err, CollectError, ColumnEncoding, Datatype, FunctionDecoder, LogDecoder, SignatureDatabase,
+    StorageDecoder, STORAGE_COLUMNS,
+};
 use indexmap::{IndexMap, IndexSet};
 use thiserror::Error;
 
@@ -39,6 +45,15 @@ pub struct Table {
 
     /// log decoder for table
     pub log_decoder: Option<LogDecoder>,
+
+    /// function call decoder for table
+    pub function_decoder: Option<FunctionDecoder>,
+
+    /// signature database for labeling selectors and topics
+    pub signature_database: Option<Arc<SignatureDatabase>>,
+
+    /// storage layouts for decoding slots
+    pub storage_decoder: Option<Arc<StorageDecoder>>,
 }
 
 impl Table {
@@ -174,6 +189,9 @@ impl Datatype {
         columns: &Option<Vec<String>>,
         sort: Option<Vec<String>>,
         log_decoder: Option<LogDecoder>,
+        function_decoder: Option<FunctionDecoder>,
+        signature_database: Option<Arc<SignatureDatabase>>,
+        storage_decoder: Option<Arc<StorageDecoder>>,
     ) -> Result<Table, SchemaError> {
         let column_types = self.column_types();
         let all_columns = column_types.keys().map(|k| k.to_string()).collect();
@@ -185,6 +203,24 @@ impl Datatype {
             exclude_columns,
             columns,
         );
+        let mut used_columns = used_columns;
+        if function_decoder.is_some() && column_types.contains_key("function_name") {
+            used_columns.insert("function_name".to_string());
+        }
+        if signature_database.is_some() {
+            for column in ["function_signature", "event_signature", "signature_unique"] {
+                if column_types.contains_key(column) {
+                    used_columns.insert(column.to_string());
+                }
+            }
+        }
+        if storage_decoder.is_some() {
+            for column in STORAGE_COLUMNS {
+                if column_types.contains_key(column) {
+                    used_columns.insert(column.to_string());
+                }
+            }
+        }
         let mut columns = IndexMap::new();
         for column in used_columns {
             let mut ctype = column_types.get(column.as_str()).ok_or(SchemaError::InvalidColumn)?;
@@ -201,6 +237,9 @@ impl Datatype {
             u256_types: u256_types.clone(),
             binary_type: binary_column_format.clone(),
             log_decoder,
+            function_decoder,
+            signature_database,
+            storage_decoder,
         };
         Ok(schema)
     }
@@ -236,6 +275,30 @@ fn compute_used_columns(
     result_set
 }
 
+/// schema of a datatype for tests, with binary u256s and the default columns if none are given
+#[cfg(test)]
+pub(crate) fn test_schema(datatype: Datatype, columns: &[&str]) -> Table {
+    let columns = if columns.is_empty() {
+        None
+    } else {
+        Some(columns.iter().map(|x| x.to_string()).collect())
+    };
+    datatype
+        .table_schema(
+            &HashSet::from_iter(vec![U256Type::Binary]),
+            &ColumnEncoding::Hex,
+            &None,
+            &None,
+            &columns,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,14 +311,36 @@ mod tests {
     fn test_table_schema_explicit_cols() {
         let cols = Some(vec!["block_number".to_string(), "block_hash".to_string()]);
         let table = Datatype::Blocks
-            .table_schema(&get_u256_types(), &ColumnEncoding::Hex, &None, &None, &cols, None, None)
+            .table_schema(
+                &get_u256_types(),
+                &ColumnEncoding::Hex,
+                &None,
+                &None,
+                &cols,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
             .unwrap();
         assert_eq!(vec!["block_number", "block_hash"], table.columns());
 
         // "all" marker support
         let cols = Some(vec!["all".to_string()]);
         let table = Datatype::Blocks
-            .table_schema(&get_u256_types(), &ColumnEncoding::Hex, &None, &None, &cols, None, None)
+            .table_schema(
+                &get_u256_types(),
+                &ColumnEncoding::Hex,
+                &None,
+                &None,
+                &cols,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
             .unwrap();
         assert_eq!(15, table.columns().len());
         assert!(table.columns().contains(&"block_hash"));
@@ -274,6 +359,9 @@ mod tests {
                 &None,
                 None,
                 None,
+                None,
+                None,
+                None,
             )
             .unwrap();
         assert_eq!(9, table.columns().len());
@@ -290,6 +378,9 @@ mod tests {
                 &None,
                 None,
                 None,
+                None,
+                None,
+                None,
             )
             .unwrap();
         assert_eq!(Some(&"chain_id"), table.columns().last());
@@ -306,6 +397,9 @@ mod tests {
                 &None,
                 None,
                 None,
+                None,
+                None,
+                None,
             )
             .unwrap();
         assert_eq!(15, table.columns().len());
@@ -317,7 +411,18 @@ mod tests {
     fn test_table_schema_exclude_cols() {
         // defaults
         let table = Datatype::Blocks
-            .table_schema(&get_u256_types(), &ColumnEncoding::Hex, &None, &None, &None, None, None)
+            .table_schema(
+                &get_u256_types(),
+                &ColumnEncoding::Hex,
+                &None,
+                &None,
+                &None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
             .unwrap();
         assert_eq!(8, table.columns().len());
         assert!(table.columns().contains(&"author"));
@@ -333,6 +438,9 @@ mod tests {
                 &None,
                 None,
                 None,
+                None,
+                None,
+                None,
             )
             .unwrap();
         assert_eq!(6, table.columns().len());
@@ -350,6 +458,9 @@ mod tests {
                 &None,
                 None,
                 None,
+                None,
+                None,
+                None,
             )
             .unwrap();
         assert_eq!(7, table.columns().len());
@@ -370,6 +481,9 @@ mod tests {
                 &None,
                 None,
                 None,
+                None,
+                None,
+                None,
             )
             .unwrap();
         assert!(!table.columns().contains(&"author"));
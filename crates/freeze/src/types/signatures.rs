@@ -17,6 +17,66 @@ lazy_static::lazy_static! {
     /// function signature of FUNCTION_ERC20_TOTAL_SUPPLY
     pub static ref FUNCTION_ERC20_TOTAL_SUPPLY: Vec<u8> = prefix_hex::decode("0x18160ddd").expect("Decoding failed");
 
+    /// function signature of FUNCTION_ERC165_SUPPORTS_INTERFACE
+    pub static ref FUNCTION_ERC165_SUPPORTS_INTERFACE: Vec<u8> = prefix_hex::decode("0x01ffc9a7").expect("Decoding failed");
+
+    /// function signature of FUNCTION_ERC721_OWNER_OF
+    pub static ref FUNCTION_ERC721_OWNER_OF: Vec<u8> = prefix_hex::decode("0x6352211e").expect("Decoding failed");
+
+    /// function signature of FUNCTION_ERC4626_ASSET
+    pub static ref FUNCTION_ERC4626_ASSET: Vec<u8> = prefix_hex::decode("0x38d52e0f").expect("Decoding failed");
+
+    /// function signature of FUNCTION_ERC4626_TOTAL_ASSETS
+    pub static ref FUNCTION_ERC4626_TOTAL_ASSETS: Vec<u8> = prefix_hex::decode("0x01e1d114").expect("Decoding failed");
+
+    /// function signature of FUNCTION_ERC4626_CONVERT_TO_ASSETS
+    pub static ref FUNCTION_ERC4626_CONVERT_TO_ASSETS: Vec<u8> = prefix_hex::decode("0x07a2d13a").expect("Decoding failed");
+
+    /// function signature of FUNCTION_UNISWAP_V2_GET_RESERVES
+    pub static ref FUNCTION_UNISWAP_V2_GET_RESERVES: Vec<u8> = prefix_hex::decode("0x0902f1ac").expect("Decoding failed");
+
+    /// function signature of FUNCTION_BEACON_IMPLEMENTATION
+    pub static ref FUNCTION_BEACON_IMPLEMENTATION: Vec<u8> = prefix_hex::decode("0x5c60da1b").expect("Decoding failed");
+
+    /// function signature of FUNCTION_GNOSIS_SAFE_MASTER_COPY
+    pub static ref FUNCTION_GNOSIS_SAFE_MASTER_COPY: Vec<u8> = prefix_hex::decode("0xa619486e").expect("Decoding failed");
+
+    /// function signature of FUNCTION_ENS_RESOLVER
+    pub static ref FUNCTION_ENS_RESOLVER: Vec<u8> = prefix_hex::decode("0x0178b8bf").expect("Decoding failed");
+
+    /// function signature of FUNCTION_ENS_NAME
+    pub static ref FUNCTION_ENS_NAME: Vec<u8> = prefix_hex::decode("0x691f3431").expect("Decoding failed");
+
+    /// function signature of FUNCTION_ENS_ADDR
+    pub static ref FUNCTION_ENS_ADDR: Vec<u8> = prefix_hex::decode("0x3b3b57de").expect("Decoding failed");
+
+    /// function signature of FUNCTION_UNISWAP_TOKEN0
+    pub static ref FUNCTION_UNISWAP_TOKEN0: Vec<u8> = prefix_hex::decode("0x0dfe1681").expect("Decoding failed");
+
+    /// function signature of FUNCTION_UNISWAP_TOKEN1
+    pub static ref FUNCTION_UNISWAP_TOKEN1: Vec<u8> = prefix_hex::decode("0xd21220a7").expect("Decoding failed");
+
+    /// function signature of FUNCTION_UNISWAP_V3_SLOT0
+    pub static ref FUNCTION_UNISWAP_V3_SLOT0: Vec<u8> = prefix_hex::decode("0x3850c7bd").expect("Decoding failed");
+
+    /// function signature of FUNCTION_UNISWAP_V3_LIQUIDITY
+    pub static ref FUNCTION_UNISWAP_V3_LIQUIDITY: Vec<u8> = prefix_hex::decode("0x1a686502").expect("Decoding failed");
+
+    /// function signature of FUNCTION_UNISWAP_V3_FEE_GROWTH_GLOBAL0
+    pub static ref FUNCTION_UNISWAP_V3_FEE_GROWTH_GLOBAL0: Vec<u8> = prefix_hex::decode("0xf3058399").expect("Decoding failed");
+
+    /// function signature of FUNCTION_UNISWAP_V3_FEE_GROWTH_GLOBAL1
+    pub static ref FUNCTION_UNISWAP_V3_FEE_GROWTH_GLOBAL1: Vec<u8> = prefix_hex::decode("0x46141319").expect("Decoding failed");
+
+    /// function signature of FUNCTION_CHAINLINK_LATEST_ROUND_DATA
+    pub static ref FUNCTION_CHAINLINK_LATEST_ROUND_DATA: Vec<u8> = prefix_hex::decode("0xfeaf968c").expect("Decoding failed");
+
+    /// function signature of FUNCTION_CHAINLINK_DESCRIPTION
+    pub static ref FUNCTION_CHAINLINK_DESCRIPTION: Vec<u8> = prefix_hex::decode("0x7284e416").expect("Decoding failed");
+
+    /// function signature of FUNCTION_CURVE_GET_DY
+    pub static ref FUNCTION_CURVE_GET_DY: Vec<u8> = prefix_hex::decode("0x5e0d443f").expect("Decoding failed");
+
     /// event hash of EVENT_ERC20_TRANSFER
     pub static ref EVENT_ERC20_TRANSFER: H256 = H256(
         prefix_hex::decode("0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")
@@ -28,4 +88,76 @@ lazy_static::lazy_static! {
         prefix_hex::decode("0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")
             .expect("Decoding failed"),
     );
+
+    /// event hash of EVENT_CHAINLINK_ANSWER_UPDATED
+    pub static ref EVENT_CHAINLINK_ANSWER_UPDATED: H256 = H256(
+        prefix_hex::decode("0x0559884fd3a460db3073b7fc896cc77986f16e378210ded43186175bf646fc5f")
+            .expect("Decoding failed"),
+    );
+
+    /// event hash of EVENT_CHAINLINK_NEW_ROUND
+    pub static ref EVENT_CHAINLINK_NEW_ROUND: H256 = H256(
+        prefix_hex::decode("0x0109fc6f55cf40689f02fbaad7af7fe7bbac8a3d2186600afc7d3e10cac60271")
+            .expect("Decoding failed"),
+    );
+
+    /// event hash of EVENT_UNISWAP_V2_SWAP
+    pub static ref EVENT_UNISWAP_V2_SWAP: H256 = H256(
+        prefix_hex::decode("0xd78ad95fa46c994b6551d0da85fc275fe613ce37657fb8d5e3d130840159d822")
+            .expect("Decoding failed"),
+    );
+
+    /// event hash of EVENT_UNISWAP_V2_MINT
+    pub static ref EVENT_UNISWAP_V2_MINT: H256 = H256(
+        prefix_hex::decode("0x4c209b5fc8ad50758f13e2e1088ba56a560dff690a1c6fef26394f4c03821c4f")
+            .expect("Decoding failed"),
+    );
+
+    /// event hash of EVENT_UNISWAP_V2_BURN
+    pub static ref EVENT_UNISWAP_V2_BURN: H256 = H256(
+        prefix_hex::decode("0xdccd412f0b1252819cb1fd330b93224ca42612892bb3f4f789976e6d81936496")
+            .expect("Decoding failed"),
+    );
+
+    /// event hash of EVENT_UNISWAP_V3_SWAP
+    pub static ref EVENT_UNISWAP_V3_SWAP: H256 = H256(
+        prefix_hex::decode("0xc42079f94a6350d7e6235f29174924f928cc2ac818eb64fed8004e115fbcca67")
+            .expect("Decoding failed"),
+    );
+
+    /// event hash of EVENT_UNISWAP_V3_MINT
+    pub static ref EVENT_UNISWAP_V3_MINT: H256 = H256(
+        prefix_hex::decode("0x7a53080ba414158be7ec69b987b5fb7d07dee101fe85488f0853ae16239d0bde")
+            .expect("Decoding failed"),
+    );
+
+    /// event hash of EVENT_UNISWAP_V3_BURN
+    pub static ref EVENT_UNISWAP_V3_BURN: H256 = H256(
+        prefix_hex::decode("0x0c396cd989a39f4459b5fa1aed6a9a8dcdbc45908acfd67e028cd568da98982c")
+            .expect("Decoding failed"),
+    );
+
+    /// storage slot of SLOT_EIP1967_IMPLEMENTATION
+    pub static ref SLOT_EIP1967_IMPLEMENTATION: H256 = H256(
+        prefix_hex::decode("0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc")
+            .expect("Decoding failed"),
+    );
+
+    /// storage slot of SLOT_EIP1967_ADMIN
+    pub static ref SLOT_EIP1967_ADMIN: H256 = H256(
+        prefix_hex::decode("0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103")
+            .expect("Decoding failed"),
+    );
+
+    /// storage slot of SLOT_EIP1967_BEACON
+    pub static ref SLOT_EIP1967_BEACON: H256 = H256(
+        prefix_hex::decode("0xa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50")
+            .expect("Decoding failed"),
+    );
+
+    /// storage slot of SLOT_EIP1822_PROXIABLE
+    pub static ref SLOT_EIP1822_PROXIABLE: H256 = H256(
+        prefix_hex::decode("0xc5f16f0fcc639fa48a6947836d9850f504798523bf8c9a3a87d5876cf622bcf7")
+            .expect("Decoding failed"),
+    );
 }
@@ -8,7 +8,8 @@ use governor::{
 };
 use tokio::sync::{AcquireError, Semaphore, SemaphorePermit};
 
-use crate::CollectError;
+use crate::{BlockOverrides, CollectError};
+use ethers::types::transaction::eip2718::TypedTransaction;
 
 /// RateLimiter based on governor crate
 pub type RateLimiter = governor::RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
@@ -22,6 +23,9 @@ pub struct Source {
     pub chain_id: u64,
     /// number of blocks per log request
     pub inner_request_size: u64,
+    /// number of blocks per request of datasets filtered by address over block ranges, such as
+    /// trace_filter, whole chunks are requested if None
+    pub filter_request_size: Option<u64>,
     /// Maximum chunks collected concurrently
     pub max_concurrent_chunks: Option<u64>,
     /// Rpc Url
@@ -55,6 +59,60 @@ pub struct Fetcher<P> {
 
 type Result<T> = ::core::result::Result<T, CollectError>;
 
+/// number of traces requested per trace_filter page
+const TRACE_FILTER_PAGE_SIZE: usize = 1000;
+
+/// page of transactions touching an address, from ots_searchTransactionsAfter
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsTransactionsPage {
+    /// transactions, most recent first
+    pub txs: Vec<Transaction>,
+    /// receipts of transactions
+    pub receipts: Vec<TransactionReceipt>,
+    /// whether page contains the most recent transactions
+    pub first_page: bool,
+    /// whether page contains the oldest transactions
+    pub last_page: bool,
+}
+
+/// creator of a contract, from ots_getContractCreator
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct OtsContractCreator {
+    /// hash of creation transaction
+    pub hash: H256,
+    /// address that deployed the contract
+    pub creator: H160,
+}
+
+/// simulation of a bundle, from eth_callBundle
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBundleResponse {
+    /// result of each transaction of bundle
+    pub results: Vec<CallBundleResult>,
+}
+
+/// simulation of a transaction of a bundle, from eth_callBundle
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBundleResult {
+    /// transaction hash
+    pub tx_hash: H256,
+    /// sender
+    pub from_address: H160,
+    /// recipient
+    #[serde(default)]
+    pub to_address: Option<H160>,
+    /// gas used
+    pub gas_used: u64,
+    /// increase in coinbase balance, as a decimal string
+    pub coinbase_diff: String,
+    /// error of failed transaction
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
 impl<P: JsonRpcClient> Fetcher<P> {
     /// Returns an array (possibly empty) of logs that match the filter
     pub async fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>> {
@@ -196,6 +254,15 @@ impl<P: JsonRpcClient> Fetcher<P> {
         Self::map_err(self.provider.trace_block(block_num).await)
     }
 
+    /// Returns traces matching filter, requesting them in pages
+    pub async fn trace_filter(&self, filter: TraceFilter) -> Result<Vec<Trace>> {
+        page_trace_filter(filter, TRACE_FILTER_PAGE_SIZE, |page_filter| async move {
+            let _permit = self.permit_request().await;
+            Self::map_err(self.provider.trace_filter(page_filter).await)
+        })
+        .await
+    }
+
     /// Returns all traces of a given transaction
     pub async fn trace_transaction(&self, tx_hash: TxHash) -> Result<Vec<Trace>> {
         let _permit = self.permit_request().await;
@@ -319,6 +386,79 @@ impl<P: JsonRpcClient> Fetcher<P> {
             .map_err(CollectError::ProviderError)
     }
 
+    /// Return output data of a contract call, or None if execution failed, ex: by reverting
+    ///
+    /// other errors, such as transport errors or rate limits, are returned as errors
+    pub async fn call_opt(
+        &self,
+        address: H160,
+        call_data: Vec<u8>,
+        block_number: BlockNumber,
+    ) -> Result<Option<Bytes>> {
+        match self.call2(address, call_data, block_number).await {
+            Ok(output) => Ok(Some(output)),
+            Err(CollectError::ProviderError(e))
+                if RpcError::as_error_response(&e).map(is_execution_error).unwrap_or(false) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Return output data of a call with a state override set and block overrides
+    pub async fn call_with_overrides(
+        &self,
+        transaction: TransactionRequest,
+        block_number: BlockNumber,
+        state_overrides: &spoof::State,
+        block_overrides: &Option<BlockOverrides>,
+    ) -> Result<Bytes> {
+        let serialize = |value: serde_json::Result<serde_json::Value>| {
+            value.map_err(|_| err("cannot serialize eth_call params"))
+        };
+        let transaction: TypedTransaction = transaction.into();
+        let state_overrides = match serialize(serde_json::to_value(state_overrides))? {
+            serde_json::Value::Null => serde_json::json!({}),
+            state_overrides => state_overrides,
+        };
+        let mut params = vec![
+            serialize(serde_json::to_value(&transaction))?,
+            serialize(serde_json::to_value(block_number))?,
+            state_overrides,
+        ];
+        if let Some(block_overrides) = block_overrides {
+            params.push(serialize(serde_json::to_value(block_overrides))?);
+        }
+        let _permit = self.permit_request().await;
+        Self::map_err(self.provider.request("eth_call", params).await)
+    }
+
+    /// Returns traces of calls executed sequentially on top of a block
+    pub async fn trace_call_many(
+        &self,
+        transactions: Vec<(TypedTransaction, Vec<TraceType>)>,
+        block_number: BlockNumber,
+    ) -> Result<Vec<BlockTrace>> {
+        let _permit = self.permit_request().await;
+        Self::map_err(self.provider.trace_call_many(transactions, Some(block_number)).await)
+    }
+
+    /// Simulate a bundle of signed transactions in the block after a block (eth_callBundle)
+    pub async fn call_bundle(
+        &self,
+        transactions: Vec<Bytes>,
+        block_number: u64,
+    ) -> Result<CallBundleResponse> {
+        let params = serde_json::json!([{
+            "txs": transactions,
+            "blockNumber": U64::from(block_number + 1),
+            "stateBlockNumber": U64::from(block_number),
+        }]);
+        let _permit = self.permit_request().await;
+        Self::map_err(self.provider.request("eth_callBundle", params).await)
+    }
+
     /// Return output data of a contract call
     pub async fn trace_call2(
         &self,
@@ -354,22 +494,61 @@ impl<P: JsonRpcClient> Fetcher<P> {
                 .map_err(CollectError::ProviderError)?
         };
 
-        let txs = if include_transaction_hashes {
+        let txs =
+            self.trace_transaction_hashes(block_number, traces.len(), include_transaction_hashes);
+        Ok((Some(block_number), txs.await?, traces))
+    }
+
+    /// get geth debug block traces of a custom tracer, as the raw json returned by the tracer
+    pub async fn geth_debug_trace_block_json(
+        &self,
+        block_number: u32,
+        options: GethDebugTracingOptions,
+        include_transaction_hashes: bool,
+    ) -> Result<(Option<u32>, Vec<Option<Vec<u8>>>, Vec<serde_json::Value>)> {
+        let results: Vec<serde_json::Value> = {
+            let _permit = self.permit_request().await;
+            let block_number = BlockNumber::Number(block_number.into());
+            Self::map_err(
+                self.provider.request("debug_traceBlockByNumber", (block_number, options)).await,
+            )?
+        };
+        // each transaction's trace is wrapped as {"txHash": .., "result": ..} or {"error": ..}
+        let mut traces = Vec::new();
+        for result in results.into_iter() {
+            let mut result = match result {
+                serde_json::Value::Object(result) => result,
+                _ => return Err(err("invalid trace result")),
+            };
+            match (result.remove("result"), result.remove("error")) {
+                (Some(trace), _) => traces.push(trace),
+                (None, Some(error)) => {
+                    return Err(CollectError::CollectError(format!("trace failed: {}", error)))
+                }
+                (None, None) => return Err(err("invalid trace result")),
+            }
+        }
+        let txs =
+            self.trace_transaction_hashes(block_number, traces.len(), include_transaction_hashes);
+        Ok((Some(block_number), txs.await?, traces))
+    }
+
+    async fn trace_transaction_hashes(
+        &self,
+        block_number: u32,
+        n_traces: usize,
+        include_transaction_hashes: bool,
+    ) -> Result<Vec<Option<Vec<u8>>>> {
+        if include_transaction_hashes {
             match self.get_block(block_number as u64).await? {
                 Some(block) => {
-                    block.transactions.iter().map(|x| Some(x.as_bytes().to_vec())).collect()
-                }
-                None => {
-                    return Err(CollectError::CollectError(
-                        "could not get block for txs".to_string(),
-                    ))
+                    Ok(block.transactions.iter().map(|x| Some(x.as_bytes().to_vec())).collect())
                 }
+                None => Err(CollectError::CollectError("could not get block for txs".to_string())),
             }
         } else {
-            vec![None; traces.len()]
-        };
-
-        Ok((Some(block_number), txs, traces))
+            Ok(vec![None; n_traces])
+        }
     }
 
     /// get geth debug block call traces
@@ -400,6 +579,48 @@ impl<P: JsonRpcClient> Fetcher<P> {
         Ok((block, txs, calls))
     }
 
+    /// get geth debug block call traces from the flatCallTracer
+    ///
+    /// the flat traces are nested back into call frames, which then have the same trace
+    /// addresses as parity traces
+    pub async fn geth_debug_trace_block_flat_calls(
+        &self,
+        block_number: u32,
+        include_transaction_hashes: bool,
+    ) -> Result<(Option<u32>, Vec<Option<Vec<u8>>>, Vec<CallFrame>)> {
+        let options = GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::JsTracer("flatCallTracer".to_string())),
+            ..Default::default()
+        };
+        let (block, txs, traces) =
+            self.geth_debug_trace_block(block_number, options, include_transaction_hashes).await?;
+        Ok((block, txs, parse_flat_call_frames(traces)?))
+    }
+
+    /// get geth debug block struct log traces
+    pub async fn geth_debug_trace_block_opcodes(
+        &self,
+        block_number: u32,
+        options: GethDebugTracingOptions,
+        include_transaction_hashes: bool,
+    ) -> Result<(Option<u32>, Vec<Option<Vec<u8>>>, Vec<DefaultFrame>)> {
+        let (block, txs, traces) =
+            self.geth_debug_trace_block(block_number, options, include_transaction_hashes).await?;
+        Ok((block, txs, parse_struct_log_frames(traces)?))
+    }
+
+    /// get geth debug block prestate traces, the state read by each transaction
+    pub async fn geth_debug_trace_block_prestate(
+        &self,
+        block_number: u32,
+        include_transaction_hashes: bool,
+    ) -> Result<(Option<u32>, Vec<Option<Vec<u8>>>, Vec<BTreeMap<H160, AccountState>>)> {
+        let (block, txs, traces) = self
+            .geth_debug_trace_block(block_number, prestate_options(), include_transaction_hashes)
+            .await?;
+        Ok((block, txs, parse_prestate_frames(traces)?))
+    }
+
     /// get geth debug block diff traces
     pub async fn geth_debug_trace_block_diffs(
         &self,
@@ -453,25 +674,44 @@ impl<P: JsonRpcClient> Fetcher<P> {
                 .await
                 .map_err(CollectError::ProviderError)?
         };
-        let traces = vec![trace];
-
-        let block_number = if include_block_number {
-            match self.get_transaction(ethers_tx).await? {
-                Some(tx) => tx.block_number.map(|x| x.as_u32()),
-                None => {
-                    return Err(CollectError::CollectError(
-                        "could not get block for txs".to_string(),
-                    ))
-                }
-            }
-        } else {
-            None
+        let block_number = self.trace_block_number(ethers_tx, include_block_number).await?;
+        Ok((block_number, vec![Some(transaction_hash)], vec![trace]))
+    }
+
+    /// get geth debug transaction trace of a custom tracer, as the raw json returned by the tracer
+    pub async fn geth_debug_trace_transaction_json(
+        &self,
+        transaction_hash: Vec<u8>,
+        options: GethDebugTracingOptions,
+        include_block_number: bool,
+    ) -> Result<(Option<u32>, Vec<Option<Vec<u8>>>, Vec<serde_json::Value>)> {
+        let ethers_tx = H256::from_slice(&transaction_hash);
+        let trace = {
+            let _permit = self.permit_request().await;
+            Self::map_err(
+                self.provider.request("debug_traceTransaction", (ethers_tx, options)).await,
+            )?
         };
+        let block_number = self.trace_block_number(ethers_tx, include_block_number).await?;
+        Ok((block_number, vec![Some(transaction_hash)], vec![trace]))
+    }
 
-        Ok((block_number, vec![Some(transaction_hash)], traces))
+    async fn trace_block_number(
+        &self,
+        transaction_hash: H256,
+        include_block_number: bool,
+    ) -> Result<Option<u32>> {
+        if include_block_number {
+            match self.get_transaction(transaction_hash).await? {
+                Some(tx) => Ok(tx.block_number.map(|x| x.as_u32())),
+                None => Err(CollectError::CollectError("could not get block for txs".to_string())),
+            }
+        } else {
+            Ok(None)
+        }
     }
 
-    /// get geth debug block call traces
+    /// get geth debug transaction call traces
     pub async fn geth_debug_trace_transaction_calls(
         &self,
         transaction_hash: Vec<u8>,
@@ -500,6 +740,96 @@ impl<P: JsonRpcClient> Fetcher<P> {
         Ok((block, txs, calls))
     }
 
+    /// get geth debug transaction call traces from the flatCallTracer
+    pub async fn geth_debug_trace_transaction_flat_calls(
+        &self,
+        transaction_hash: Vec<u8>,
+        include_block_number: bool,
+    ) -> Result<(Option<u32>, Vec<Option<Vec<u8>>>, Vec<CallFrame>)> {
+        let options = GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::JsTracer("flatCallTracer".to_string())),
+            ..Default::default()
+        };
+        let (block, txs, traces) = self
+            .geth_debug_trace_transaction(transaction_hash, options, include_block_number)
+            .await?;
+        Ok((block, txs, parse_flat_call_frames(traces)?))
+    }
+
+    /// get flatCallTracer traces of a call, with a state override set and block overrides
+    ///
+    /// the flat traces are in the same format as the traces of parity's trace_call
+    pub async fn geth_debug_trace_call_flat_calls(
+        &self,
+        transaction: TransactionRequest,
+        block_number: BlockNumber,
+        state_overrides: spoof::State,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<Vec<TransactionTrace>> {
+        let options = GethDebugTracingCallOptions {
+            tracing_options: GethDebugTracingOptions {
+                tracer: Some(GethDebugTracerType::JsTracer("flatCallTracer".to_string())),
+                ..Default::default()
+            },
+            state_overrides: Some(state_overrides),
+        };
+        // block overrides are not part of ethers' call options, so are added to the json
+        let serialize = |value: serde_json::Result<serde_json::Value>| {
+            value.map_err(|_| err("cannot serialize debug_traceCall params"))
+        };
+        let mut options = serialize(serde_json::to_value(options))?;
+        if let (Some(block_overrides), Some(options)) = (block_overrides, options.as_object_mut()) {
+            options.insert(
+                "blockOverrides".to_string(),
+                serialize(serde_json::to_value(block_overrides))?,
+            );
+        }
+        let transaction: TypedTransaction = transaction.into();
+        let params = vec![
+            serialize(serde_json::to_value(&transaction))?,
+            serialize(serde_json::to_value(block_number))?,
+            options,
+        ];
+        let trace: GethTrace = {
+            let _permit = self.permit_request().await;
+            Self::map_err(self.provider.request("debug_traceCall", params).await)?
+        };
+        match trace {
+            GethTrace::Unknown(value) => serde_json::from_value(value)
+                .map_err(|_| err("cannot deserialize flat call traces")),
+            _ => Err(CollectError::CollectError("invalid trace result".to_string())),
+        }
+    }
+
+    /// get geth debug transaction struct log traces
+    pub async fn geth_debug_trace_transaction_opcodes(
+        &self,
+        transaction_hash: Vec<u8>,
+        options: GethDebugTracingOptions,
+        include_block_number: bool,
+    ) -> Result<(Option<u32>, Vec<Option<Vec<u8>>>, Vec<DefaultFrame>)> {
+        let (block, txs, traces) = self
+            .geth_debug_trace_transaction(transaction_hash, options, include_block_number)
+            .await?;
+        Ok((block, txs, parse_struct_log_frames(traces)?))
+    }
+
+    /// get geth debug transaction prestate traces, the state read by the transaction
+    pub async fn geth_debug_trace_transaction_prestate(
+        &self,
+        transaction_hash: Vec<u8>,
+        include_block_number: bool,
+    ) -> Result<(Option<u32>, Vec<Option<Vec<u8>>>, Vec<BTreeMap<H160, AccountState>>)> {
+        let (block, txs, traces) = self
+            .geth_debug_trace_transaction(
+                transaction_hash,
+                prestate_options(),
+                include_block_number,
+            )
+            .await?;
+        Ok((block, txs, parse_prestate_frames(traces)?))
+    }
+
     /// get geth debug block diff traces
     pub async fn geth_debug_trace_transaction_diffs(
         &self,
@@ -531,6 +861,30 @@ impl<P: JsonRpcClient> Fetcher<P> {
         Ok((block, txs, diffs))
     }
 
+    /// Returns a page of transactions touching an address after a block (Otterscan api)
+    pub async fn ots_search_transactions_after(
+        &self,
+        address: H160,
+        block_number: u64,
+        page_size: u64,
+    ) -> Result<OtsTransactionsPage> {
+        let _permit = self.permit_request().await;
+        Self::map_err(
+            self.provider
+                .request("ots_searchTransactionsAfter", (address, block_number, page_size))
+                .await,
+        )
+    }
+
+    /// Returns the creator of a contract, or None if address is not a contract (Otterscan api)
+    pub async fn ots_get_contract_creator(
+        &self,
+        address: H160,
+    ) -> Result<Option<OtsContractCreator>> {
+        let _permit = self.permit_request().await;
+        Self::map_err(self.provider.request("ots_getContractCreator", [address]).await)
+    }
+
     async fn permit_request(
         &self,
     ) -> Option<::core::result::Result<SemaphorePermit<'_>, AcquireError>> {
@@ -549,10 +903,32 @@ impl<P: JsonRpcClient> Fetcher<P> {
     }
 }
 
+impl<P: PubsubClient> Fetcher<P> {
+    /// Subscribe to hashes of transactions entering the mempool
+    pub async fn subscribe_pending_txs(&self) -> Result<SubscriptionStream<'_, P, TxHash>> {
+        Self::map_err(self.provider.subscribe_pending_txs().await)
+    }
+
+    /// Subscribe to headers of new blocks
+    pub async fn subscribe_blocks(&self) -> Result<SubscriptionStream<'_, P, Block<TxHash>>> {
+        Self::map_err(self.provider.subscribe_blocks().await)
+    }
+}
+
 use crate::err;
 use std::collections::BTreeMap;
 use tokio::task;
 
+/// messages of errors raised by the evm while executing a call
+const EXECUTION_ERRORS: [&str; 5] =
+    ["revert", "invalid opcode", "out of gas", "stack underflow", "invalid jump"];
+
+/// whether an error response is a failed execution rather than a failure of the node
+fn is_execution_error(error: &JsonRpcError) -> bool {
+    // code 3 is used by geth and others for reverts with data
+    error.code == 3 || EXECUTION_ERRORS.iter().any(|x| error.message.to_lowercase().contains(x))
+}
+
 fn parse_geth_diff_object(
     map: ethers::utils::__serde_json::Map<String, ethers::utils::__serde_json::Value>,
 ) -> Result<DiffMode> {
@@ -565,6 +941,140 @@ fn parse_geth_diff_object(
     Ok(DiffMode { pre, post })
 }
 
+fn parse_struct_log_frames(traces: Vec<GethTrace>) -> Result<Vec<DefaultFrame>> {
+    let mut frames = Vec::new();
+    for trace in traces.into_iter() {
+        match trace {
+            GethTrace::Known(GethTraceFrame::Default(frame)) => frames.push(frame),
+            _ => return Err(CollectError::CollectError("invalid trace result".to_string())),
+        }
+    }
+    Ok(frames)
+}
+
+fn prestate_options() -> GethDebugTracingOptions {
+    let tracer = GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::PreStateTracer);
+    let config = GethDebugTracerConfig::BuiltInTracer(
+        GethDebugBuiltInTracerConfig::PreStateTracer(PreStateConfig { diff_mode: Some(false) }),
+    );
+    GethDebugTracingOptions {
+        tracer: Some(tracer),
+        tracer_config: Some(config),
+        ..Default::default()
+    }
+}
+
+fn parse_prestate_frames(traces: Vec<GethTrace>) -> Result<Vec<BTreeMap<H160, AccountState>>> {
+    let mut prestates = Vec::new();
+    for trace in traces.into_iter() {
+        match trace {
+            GethTrace::Known(GethTraceFrame::PreStateTracer(PreStateFrame::Default(
+                PreStateMode(prestate),
+            ))) => prestates.push(prestate),
+            // transactions that read no state give an empty object
+            GethTrace::Known(GethTraceFrame::NoopTracer(_)) => prestates.push(BTreeMap::new()),
+            GethTrace::Unknown(value) => prestates.push(
+                serde_json::from_value(value).map_err(|_| err("cannot deserialize prestate"))?,
+            ),
+            _ => return Err(CollectError::CollectError("invalid trace result".to_string())),
+        }
+    }
+    Ok(prestates)
+}
+
+fn parse_flat_call_frames(traces: Vec<GethTrace>) -> Result<Vec<CallFrame>> {
+    let mut frames = Vec::new();
+    for trace in traces.into_iter() {
+        let flat_traces: Vec<Trace> = match trace {
+            GethTrace::Unknown(value) => serde_json::from_value(value)
+                .map_err(|_| err("cannot deserialize flat call traces"))?,
+            _ => return Err(CollectError::CollectError("invalid trace result".to_string())),
+        };
+        frames.push(nest_flat_traces(flat_traces)?);
+    }
+    Ok(frames)
+}
+
+/// nest the flat traces of a transaction into a tree of call frames by their trace addresses
+fn nest_flat_traces(traces: Vec<Trace>) -> Result<CallFrame> {
+    let mut traces = traces.into_iter();
+    let mut root = match traces.next() {
+        Some(trace) if trace.trace_address.is_empty() => flat_trace_to_call_frame(trace),
+        _ => return Err(err("flat call traces are missing top level call")),
+    };
+    for trace in traces {
+        let (position, parent_address) = match trace.trace_address.split_last() {
+            Some((position, parent_address)) => (*position, parent_address.to_vec()),
+            None => return Err(err("flat call traces have multiple top level calls")),
+        };
+        let mut parent = &mut root;
+        for index in parent_address.into_iter() {
+            parent = parent
+                .calls
+                .as_mut()
+                .and_then(|calls| calls.get_mut(index))
+                .ok_or(err("flat call trace without parent"))?;
+        }
+        let calls = parent.calls.get_or_insert_with(Vec::new);
+        if calls.len() != position {
+            return Err(err("flat call traces are out of order"))
+        }
+        calls.push(flat_trace_to_call_frame(trace));
+    }
+    Ok(root)
+}
+
+fn flat_trace_to_call_frame(trace: Trace) -> CallFrame {
+    let mut frame = CallFrame { error: trace.error, ..Default::default() };
+    match trace.action {
+        Action::Call(call) => {
+            frame.typ = match call.call_type {
+                CallType::CallCode => "CALLCODE",
+                CallType::DelegateCall => "DELEGATECALL",
+                CallType::StaticCall => "STATICCALL",
+                CallType::Call | CallType::None => "CALL",
+            }
+            .to_string();
+            frame.from = call.from;
+            frame.to = Some(NameOrAddress::Address(call.to));
+            frame.value = Some(call.value);
+            frame.gas = call.gas;
+            frame.input = call.input;
+        }
+        Action::Create(create) => {
+            frame.typ = "CREATE".to_string();
+            frame.from = create.from;
+            frame.value = Some(create.value);
+            frame.gas = create.gas;
+            frame.input = create.init;
+        }
+        Action::Suicide(suicide) => {
+            frame.typ = "SELFDESTRUCT".to_string();
+            frame.from = suicide.address;
+            frame.to = Some(NameOrAddress::Address(suicide.refund_address));
+            frame.value = Some(suicide.balance);
+        }
+        Action::Reward(reward) => {
+            frame.typ = "REWARD".to_string();
+            frame.to = Some(NameOrAddress::Address(reward.author));
+            frame.value = Some(reward.value);
+        }
+    }
+    match trace.result {
+        Some(Res::Call(result)) => {
+            frame.gas_used = result.gas_used;
+            frame.output = Some(result.output);
+        }
+        Some(Res::Create(result)) => {
+            frame.gas_used = result.gas_used;
+            frame.output = Some(result.code);
+            frame.to = Some(NameOrAddress::Address(result.address));
+        }
+        Some(Res::None) | None => {}
+    }
+    frame
+}
+
 impl Source {
     /// get gas used by transactions in block
     pub async fn get_txs_gas_used(&self, block: &Block<Transaction>) -> Result<Vec<u64>> {
@@ -623,3 +1133,87 @@ async fn get_txs_gas_used_per_tx<P: JsonRpcClient + 'static>(
 
     Ok(gas_used)
 }
+
+/// request pages of a trace filter until a page is shorter than page_size
+async fn page_trace_filter<F, Fut>(
+    filter: TraceFilter,
+    page_size: usize,
+    fetch_page: F,
+) -> Result<Vec<Trace>>
+where
+    F: Fn(TraceFilter) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<Trace>>>,
+{
+    let mut traces = Vec::new();
+    loop {
+        let page = fetch_page(filter.clone().after(traces.len()).count(page_size)).await?;
+        let n_traces = page.len();
+        traces.extend(page);
+        if n_traces < page_size {
+            return Ok(traces)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_execution_error() {
+        let error = |code: i64, message: &str| JsonRpcError {
+            code,
+            message: message.to_string(),
+            data: None,
+        };
+        assert!(is_execution_error(&error(3, "execution reverted: not owner")));
+        assert!(is_execution_error(&error(-32000, "execution reverted")));
+        assert!(is_execution_error(&error(-32000, "invalid opcode: INVALID")));
+        assert!(!is_execution_error(&error(-32005, "limit exceeded")));
+        assert!(!is_execution_error(&error(-32000, "header not found")));
+    }
+
+    #[tokio::test]
+    async fn test_page_trace_filter() {
+        let trace = serde_json::from_str::<Trace>(
+            r#"{"action": {"callType": "call", "from": "0x0000000000000000000000000000000000000001", "gas": "0x10", "input": "0x", "to": "0x0000000000000000000000000000000000000002", "value": "0x0"}, "blockHash": "0x0000000000000000000000000000000000000000000000000000000000000000", "blockNumber": 1, "result": {"gasUsed": "0x1", "output": "0x"}, "subtraces": 0, "traceAddress": [], "type": "call"}"#,
+        )
+        .unwrap();
+        let offsets = std::sync::Mutex::new(Vec::new());
+        let fetch_page = |page_filter: TraceFilter| {
+            let page_filter = serde_json::to_value(page_filter).unwrap();
+            offsets.lock().unwrap().push(page_filter["after"].as_u64().unwrap());
+            // 5 traces in total, served in pages of at most 2
+            let n_remaining = 5 - page_filter["after"].as_u64().unwrap() as usize;
+            let page = vec![trace.clone(); n_remaining.min(2)];
+            async move { Ok(page) }
+        };
+        let traces = page_trace_filter(TraceFilter::default(), 2, fetch_page).await.unwrap();
+        assert_eq!(traces.len(), 5);
+        assert_eq!(*offsets.lock().unwrap(), vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_nest_flat_traces() {
+        let trace = |trace_address: &str, call_type: &str| {
+            let json = format!(
+                r#"{{"action": {{"callType": "{}", "from": "0x0000000000000000000000000000000000000001", "gas": "0x10", "input": "0x", "to": "0x0000000000000000000000000000000000000002", "value": "0x0"}}, "blockHash": "0x0000000000000000000000000000000000000000000000000000000000000000", "blockNumber": 1, "result": {{"gasUsed": "0x1", "output": "0x"}}, "subtraces": 0, "traceAddress": [{}], "type": "call"}}"#,
+                call_type, trace_address
+            );
+            serde_json::from_str::<Trace>(&json).unwrap()
+        };
+        let traces = vec![
+            trace("", "call"),
+            trace("0", "delegatecall"),
+            trace("0, 0", "staticcall"),
+            trace("1", "call"),
+        ];
+        let root = nest_flat_traces(traces).unwrap();
+        let calls = root.calls.unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].typ, "DELEGATECALL");
+        assert_eq!(calls[0].calls.as_ref().unwrap()[0].typ, "STATICCALL");
+
+        assert!(nest_flat_traces(vec![trace("", "call"), trace("1", "call")]).is_err());
+    }
+}
@@ -6,9 +6,9 @@ use thousands::Separable;
 
 use crate::{
     chunks::chunk_ops::ValueToString, ChunkData, ChunkStats, CollectError, ColumnType, Datatype,
-    Dim, ExecutionEnv, FileOutput, MultiDatatype, Partition, Query, Source, Table,
+    Dim, ExecutionEnv, FileOutput, MultiDatatype, Partition, Query, Source, StreamOptions, Table,
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const TITLE_R: u8 = 0;
 const TITLE_G: u8 = 225;
@@ -28,6 +28,19 @@ pub struct FreezeSummary {
     pub errored: Vec<(Option<Partition>, CollectError)>,
 }
 
+/// summary of a stream
+#[derive(Debug, Default)]
+pub(crate) struct StreamSummary {
+    /// files written
+    pub(crate) written: Vec<PathBuf>,
+    /// files skipped because they already exist
+    pub(crate) skipped: Vec<PathBuf>,
+    /// rows written across all files
+    pub(crate) n_rows: u64,
+    /// errors that did not stop the stream
+    pub(crate) errored: Vec<CollectError>,
+}
+
 /// print all datasets
 pub fn print_all_datasets() {
     print_header("cryo datasets");
@@ -268,6 +281,7 @@ fn print_chunks(chunks: &[Partition], align: Option<bool>, reorg_buffer: Option<
         (Dim::CallData, stats.call_datas),
         (Dim::Address, stats.addresses),
         (Dim::Contract, stats.contracts),
+        (Dim::FromAddress, stats.from_addresses),
         (Dim::ToAddress, stats.to_addresses),
         (Dim::Slot, stats.slots),
         (Dim::Topic0, stats.topic0s),
@@ -388,20 +402,8 @@ pub(crate) fn print_cryo_conclusion(
     println!();
 
     if !freeze_summary.errored.is_empty() {
-        print_header_error("error summary");
-        println!("(errors in {} chunks)", freeze_summary.errored.len());
-        let mut error_counts: HashMap<String, usize> = HashMap::new();
-        for (_partition, error) in freeze_summary.errored.iter() {
-            *error_counts.entry(error.to_string()).or_insert(0) += 1;
-        }
-        for (error, count) in error_counts.iter().take(10) {
-            println!("- {} ({}x)", error, count);
-        }
-        if error_counts.len() > 10 {
-            println!("...")
-        }
-        println!();
-        println!();
+        let errors = freeze_summary.errored.iter().map(|(_partition, error)| error);
+        print_error_summary(errors, "chunks");
     }
 
     let duration = match t_end.duration_since(env.t_start) {
@@ -464,6 +466,115 @@ pub(crate) fn print_cryo_conclusion(
     );
 }
 
+fn print_error_summary<'a>(errors: impl Iterator<Item = &'a CollectError>, unit: &str) {
+    let mut n_errors = 0;
+    let mut error_counts: HashMap<String, usize> = HashMap::new();
+    for error in errors {
+        n_errors += 1;
+        *error_counts.entry(error.to_string()).or_insert(0) += 1;
+    }
+    print_header_error("error summary");
+    println!("(errors in {} {})", n_errors, unit);
+    for (error, count) in error_counts.iter().take(10) {
+        println!("- {} ({}x)", error, count);
+    }
+    if error_counts.len() > 10 {
+        println!("...")
+    }
+    println!();
+    println!();
+}
+
+pub(crate) fn print_stream_intro(
+    query: &Query,
+    source: &Source,
+    sink: &FileOutput,
+    env: &ExecutionEnv,
+    options: &StreamOptions,
+) {
+    print_header("cryo parameters");
+    print_bullet("version", super::reports::CRYO_VERSION);
+    let datatype_strs: Vec<_> = query.schemas.keys().map(|d| d.name()).collect();
+    print_bullet("data", "");
+    print_bullet_indent("datatypes", datatype_strs.join(", "), 4);
+
+    print_bullet("source", "");
+    print_bullet_indent("network", &sink.prefix, 4);
+    print_bullet_indent("rpc url", &source.rpc_url, 4);
+    print_bullet_indent("websocket url", &options.ws_url, 4);
+
+    print_bullet("output", "");
+    let rotate_interval = format!("{} seconds", options.rotate_interval.separate_with_commas());
+    print_bullet_indent("file interval", rotate_interval, 4);
+    match options.duration {
+        Some(duration) => print_bullet_indent(
+            "stream duration",
+            format!("{} seconds", duration.separate_with_commas()),
+            4,
+        ),
+        None => print_bullet_indent("stream duration", "until interrupted", 4),
+    };
+    print_bullet_indent("output format", sink.format.as_str(), 4);
+    print_bullet_indent("output dir", sink.output_dir.clone().to_string_lossy(), 4);
+
+    print_schemas(&query.schemas);
+
+    if env.dry {
+        println!("\n\n[dry run, exiting]");
+    } else {
+        println!();
+        println!();
+        print_header("streaming data");
+    }
+}
+
+pub(crate) fn print_stream_file(path: &Path, n_rows: Option<usize>) {
+    let name = path.file_name().map(|x| x.to_string_lossy()).unwrap_or_default();
+    match n_rows {
+        Some(n_rows) => print_bullet(name, format!("{} rows", n_rows.separate_with_commas())),
+        None => print_bullet(name, "skipped, file already exists"),
+    }
+}
+
+pub(crate) fn print_stream_conclusion(stream_summary: &StreamSummary, env: &ExecutionEnv) {
+    let env = env.clone().set_end_time();
+    let t_end = match env.t_end {
+        Some(t_end) => t_end,
+        _ => return,
+    };
+    let dt_data_done: DateTime<Local> = t_end.into();
+    let dt_start: DateTime<Local> = env.t_start.into();
+
+    println!();
+    println!();
+    println!("started at {}", dt_start.format("%Y-%m-%d %H:%M:%S%.3f"));
+    println!("   done at {}", dt_data_done.format("%Y-%m-%d %H:%M:%S%.3f").to_string().as_str());
+    println!();
+    println!();
+
+    if !stream_summary.errored.is_empty() {
+        print_error_summary(stream_summary.errored.iter(), "operations");
+    }
+
+    let duration = match t_end.duration_since(env.t_start) {
+        Ok(duration) => duration,
+        Err(_e) => {
+            println!("error computing system time, aborting");
+            return
+        }
+    };
+    let seconds = duration.as_secs();
+    let millis = duration.subsec_millis();
+    let total_time = (seconds as f64) + (duration.subsec_nanos() as f64) / 1e9;
+    let duration_string = format!("{}.{:03} seconds", seconds, millis);
+
+    print_header("collection summary");
+    print_bullet("total duration", duration_string);
+    print_bullet("files written", stream_summary.written.len().separate_with_commas());
+    print_bullet("files skipped", stream_summary.skipped.len().separate_with_commas());
+    print_unit_speeds("pending transactions".to_string(), stream_summary.n_rows, total_time);
+}
+
 macro_rules! print_dim_speed {
     ($chunks:expr, $partition_by:expr, $total_time:expr, $name:ident, $dim:expr, $n_dts:expr) => {
         if $partition_by.contains(&$dim) {
@@ -58,9 +58,29 @@ use cryo_freeze::collect;
         topic2 = None,
         topic3 = None,
         inner_request_size = 1,
+        filter_request_size = None,
         verbose = false,
         no_verbose = false,
         event_signature = None,
+        ws_rpc = None,
+        stream_duration = None,
+        rotate_interval = 3600,
+        price_sources = None,
+        token_ids = None,
+        transfers = None,
+        spot_check = None,
+        function_signature = None,
+        abi = None,
+        signatures = None,
+        storage_layout = None,
+        mapping_keys = None,
+        flat_call_tracer = false,
+        tracer = None,
+        tracer_config = None,
+        tracer_columns = None,
+        call_overrides = None,
+        bundles = None,
+        call_bundle = false,
     )
 )]
 #[allow(clippy::too_many_arguments)]
@@ -116,9 +136,29 @@ pub fn _collect(
     topic2: Option<Vec<String>>,
     topic3: Option<Vec<String>>,
     inner_request_size: u64,
+    filter_request_size: Option<u64>,
     verbose: bool,
     no_verbose: bool,
     event_signature: Option<String>,
+    ws_rpc: Option<String>,
+    stream_duration: Option<u64>,
+    rotate_interval: u64,
+    price_sources: Option<String>,
+    token_ids: Option<Vec<String>>,
+    transfers: Option<Vec<String>>,
+    spot_check: Option<u64>,
+    function_signature: Option<Vec<String>>,
+    abi: Option<String>,
+    signatures: Option<Vec<String>>,
+    storage_layout: Option<Vec<String>>,
+    mapping_keys: Option<Vec<String>>,
+    flat_call_tracer: bool,
+    tracer: Option<String>,
+    tracer_config: Option<String>,
+    tracer_columns: Option<Vec<String>>,
+    call_overrides: Option<String>,
+    bundles: Option<String>,
+    call_bundle: bool,
 ) -> PyResult<&PyAny> {
     if let Some(command) = command {
         pyo3_asyncio::tokio::future_into_py(py, async move {
@@ -178,9 +218,29 @@ pub fn _collect(
             topic2,
             topic3,
             inner_request_size,
+            filter_request_size,
             verbose,
             no_verbose,
             event_signature,
+            ws_rpc,
+            stream_duration,
+            rotate_interval,
+            price_sources,
+            token_ids,
+            transfers,
+            spot_check,
+            function_signature,
+            abi,
+            signatures,
+            storage_layout,
+            mapping_keys,
+            flat_call_tracer,
+            tracer,
+            tracer_config,
+            tracer_columns,
+            call_overrides,
+            bundles,
+            call_bundle,
         };
         pyo3_asyncio::tokio::future_into_py(py, async move {
             match run_collect(args).await {
@@ -55,6 +55,29 @@ pub fn to_df(attrs: TokenStream, input: TokenStream) -> TokenStream {
         })
         .collect();
 
+    let has_function_cols = field_names_and_types.iter().any(|(name, _)| name == "function_cols");
+    let function_cols_processing = if has_function_cols {
+        quote! {
+            if let Some(decoder) = &schema.function_decoder {
+                let n_rows = self.n_rows as usize;
+                cols.extend(decoder.make_series(self.function_cols, n_rows, &schema.binary_type));
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let has_trace_cols = field_names_and_types.iter().any(|(name, _)| name == "trace_cols");
+    let trace_cols_processing = if has_trace_cols {
+        quote! {
+            for (name, values) in self.trace_cols.into_iter() {
+                cols.push(Series::new(name.as_str(), values));
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     fn map_type_to_column_type(ty: &syn::Type) -> Option<proc_macro2::TokenStream> {
         match quote!(#ty).to_string().as_str() {
             "Vec < bool >" => Some(quote! { ColumnType::Boolean }),
@@ -92,7 +115,11 @@ pub fn to_df(attrs: TokenStream, input: TokenStream) -> TokenStream {
         if let Some(column_type) = map_type_to_column_type(ty) {
             let field_name_str = format!("{}", quote!(#name));
             column_types.push(quote! { (#field_name_str, #column_type) });
-        } else if name != "n_rows" && name != "event_cols" {
+        } else if name != "n_rows" &&
+            name != "event_cols" &&
+            name != "function_cols" &&
+            name != "trace_cols"
+        {
             println!("invalid column type for {name} in table {}", datatype_str);
         }
     }
@@ -118,6 +145,10 @@ pub fn to_df(attrs: TokenStream, input: TokenStream) -> TokenStream {
 
                 #(#field_processing)*
 
+                #function_cols_processing
+
+                #trace_cols_processing
+
                 if self.chain_id.len() == 0 {
                     with_series!(cols, "chain_id", vec![chain_id; self.n_rows as usize], schema);
                 } else {